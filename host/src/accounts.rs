@@ -0,0 +1,299 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use runtime::{Account, Pubkey};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// Account entry as it appears in an accounts file.
+/// Keys are base58 strings and account data is base64 encoded.
+#[derive(Deserialize)]
+struct AccountEntry {
+    pubkey: String,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+    lamports: u64,
+    #[serde(default)]
+    data: String,
+    owner: String,
+    #[serde(default)]
+    executable: bool,
+    #[serde(default)]
+    rent_epoch: u64,
+}
+
+impl TryFrom<AccountEntry> for Account {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: AccountEntry) -> Result<Self> {
+        Ok(Account {
+            pubkey: Pubkey::try_from(entry.pubkey).map_err(|e| anyhow!(e))?,
+            is_signer: entry.is_signer,
+            is_writable: entry.is_writable,
+            lamports: entry.lamports,
+            data: STANDARD
+                .decode(entry.data)
+                .context("Invalid base64 account data")?,
+            owner: Pubkey::try_from(entry.owner).map_err(|e| anyhow!(e))?,
+            executable: entry.executable,
+            rent_epoch: entry.rent_epoch,
+        })
+    }
+}
+
+/// Account as written by `solana account --output json`, the same format
+/// `solana-test-validator --account` loads. Data is a `[data, encoding]` pair.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DumpedAccount {
+    lamports: u64,
+    data: (String, String),
+    owner: String,
+    executable: bool,
+    rent_epoch: u64,
+}
+
+/// Account dump, optionally carrying the instruction's flags when listed in an accounts file.
+#[derive(Deserialize)]
+struct DumpEntry {
+    pubkey: String,
+    account: DumpedAccount,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+}
+
+impl TryFrom<DumpEntry> for Account {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: DumpEntry) -> Result<Self> {
+        let (data, encoding) = entry.account.data;
+        if encoding != "base64" {
+            bail!(
+                "Unsupported account data encoding '{}', dump the account with --output json",
+                encoding
+            );
+        }
+        Account::try_from(AccountEntry {
+            pubkey: entry.pubkey,
+            is_signer: entry.is_signer,
+            is_writable: entry.is_writable,
+            lamports: entry.account.lamports,
+            data,
+            owner: entry.account.owner,
+            executable: entry.account.executable,
+            rent_epoch: entry.account.rent_epoch,
+        })
+    }
+}
+
+/// Loads the instruction's accounts from a JSON file containing an array of entries,
+/// in the order the program expects to receive them. An entry is either an account
+/// or an account dump, which is told apart by its nested `account` object.
+pub fn load_accounts(path: &Path) -> Result<Vec<Account>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read accounts file {}", path.display()))?;
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse accounts file {}", path.display()))?;
+
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let account = if entry.get("account").is_some() {
+                serde_json::from_value::<DumpEntry>(entry)
+                    .map_err(anyhow::Error::from)
+                    .and_then(Account::try_from)
+            } else {
+                serde_json::from_value::<AccountEntry>(entry)
+                    .map_err(anyhow::Error::from)
+                    .and_then(Account::try_from)
+            };
+            account.with_context(|| format!("Invalid account at index {}", index))
+        })
+        .collect()
+}
+
+/// Loads an account from a `solana account --output json` dump, given as `PATH` or as
+/// `ADDRESS=PATH` to load it at another address like `solana-test-validator --account`.
+/// A spec whose text before the first `=` is not an address is read as a path.
+/// The account is neither a signer nor writable.
+pub fn load_account_dump(spec: &str) -> Result<Account> {
+    let override_address = spec.split_once('=').and_then(|(address, path)| {
+        Pubkey::try_from(address.to_string())
+            .ok()
+            .map(|address| (address, path))
+    });
+    let (address, path) = match override_address {
+        Some((address, path)) => (Some(address), path),
+        None => (None, spec),
+    };
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read account dump {}", path))?;
+    let mut entry: DumpEntry = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse account dump {}", path))?;
+    entry.is_signer = false;
+    entry.is_writable = false;
+    if let Some(address) = address {
+        entry.pubkey = address.to_string();
+    }
+    Account::try_from(entry).with_context(|| format!("Invalid account dump {}", path))
+}
+
+/// Prints the accounts committed to the journal.
+pub fn print_accounts(accounts: &[Account]) {
+    println!("Accounts:");
+    for account in accounts {
+        println!(
+            "  {}: {} lamports, {} bytes, owner {}",
+            account.pubkey,
+            account.lamports,
+            account.data.len(),
+            account.owner
+        );
+    }
+}
+
+/// Prints how each account changed between the program's input and output.
+pub fn print_state_diff(before: &[Account], after: &[Account]) {
+    println!("State diff:");
+    let mut changed = false;
+    for (pre, post) in before.iter().zip(after) {
+        if pre == post {
+            continue;
+        }
+        changed = true;
+        println!("  {}:", pre.pubkey);
+        if pre.lamports != post.lamports {
+            println!("    lamports: {} -> {}", pre.lamports, post.lamports);
+        }
+        if pre.owner != post.owner {
+            println!("    owner: {} -> {}", pre.owner, post.owner);
+        }
+        if pre.data.len() != post.data.len() {
+            println!("    data length: {} -> {}", pre.data.len(), post.data.len());
+        }
+        let modified = pre
+            .data
+            .iter()
+            .zip(&post.data)
+            .filter(|(a, b)| a != b)
+            .count();
+        if modified > 0 {
+            println!("    data: {} bytes modified", modified);
+        }
+    }
+    if !changed {
+        println!("  (no changes)");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const ADDRESS: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+    const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    /// An account as printed by `solana account --output json`.
+    fn dump(encoding: &str) -> String {
+        format!(
+            r#"{{
+                "pubkey": "{}",
+                "account": {{
+                    "lamports": 1461600,
+                    "data": ["AQIDBA==", "{}"],
+                    "owner": "{}",
+                    "executable": false,
+                    "rentEpoch": 18446744073709551615,
+                    "space": 4
+                }}
+            }}"#,
+            ADDRESS, encoding, TOKEN_PROGRAM
+        )
+    }
+
+    fn write_fixture(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("zksol-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn account_dump() {
+        let path = write_fixture("dump.json", &dump("base64"));
+        let account = load_account_dump(path.to_str().unwrap()).unwrap();
+        assert_eq!(account.pubkey.to_string(), ADDRESS);
+        assert_eq!(account.owner.to_string(), TOKEN_PROGRAM);
+        assert_eq!(account.lamports, 1_461_600);
+        assert_eq!(account.data, [1, 2, 3, 4]);
+        assert_eq!(account.rent_epoch, u64::MAX);
+        assert!(!account.is_signer && !account.is_writable && !account.executable);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn account_dump_at_address() {
+        let path = write_fixture("dump-address.json", &dump("base64"));
+        let address = "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi";
+        let account = load_account_dump(&format!("{}={}", address, path.display())).unwrap();
+        assert_eq!(account.pubkey.to_string(), address);
+        assert_eq!(account.data, [1, 2, 3, 4]);
+
+        // Not an address, so the whole spec is a path that does not exist
+        let invalid = load_account_dump(&format!("not-a-key={}", path.display()));
+        assert!(invalid.is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn account_dump_path_with_equals() {
+        let path = write_fixture("a=b.json", &dump("base64"));
+        let account = load_account_dump(path.to_str().unwrap()).unwrap();
+        assert_eq!(account.pubkey.to_string(), ADDRESS);
+        assert_eq!(account.data, [1, 2, 3, 4]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reject_unsupported_encoding() {
+        for encoding in ["base58", "base64+zstd", "jsonParsed"] {
+            let path = write_fixture(&format!("dump-{}.json", encoding), &dump(encoding));
+            let error = load_account_dump(path.to_str().unwrap()).unwrap_err();
+            assert!(
+                format!("{:#}", error).contains("Unsupported account data encoding"),
+                "{:#}",
+                error
+            );
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn mixed_accounts_file() {
+        let contents = format!(
+            r#"[
+                {{ "pubkey": "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi",
+                   "owner": "11111111111111111111111111111111", "lamports": 5, "is_signer": true }},
+                {}
+            ]"#,
+            dump("base64").replacen('{', r#"{ "is_writable": true,"#, 1)
+        );
+        let path = write_fixture("accounts.json", &contents);
+        let accounts = load_accounts(&path).unwrap();
+
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].lamports, 5);
+        assert!(accounts[0].is_signer && !accounts[0].is_writable);
+        assert_eq!(accounts[1].pubkey.to_string(), ADDRESS);
+        assert_eq!(accounts[1].rent_epoch, u64::MAX);
+        assert!(!accounts[1].is_signer && accounts[1].is_writable);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -1,51 +1,380 @@
-use std::fs;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
 // These constants represent the RISC-V ELF and the image ID generated by risc0-build.
 // The ELF is used for proving and the ID is used for verification.
 use methods::{SOL_ELF, SOL_ID};
-use risc0_zkvm::{default_prover, ExecutorEnv};
+use risc0_zkvm::{default_executor, default_prover, ExecutorEnv, Receipt};
+use runtime::{
+    input::{
+        ExecutionParameters, Input, DEFAULT_COMPUTE_UNIT_LIMIT, DEFAULT_HEAP_SIZE,
+        DEFAULT_MAX_CALL_DEPTH, DEFAULT_MAX_SBPF_VERSION, DEFAULT_MIN_SBPF_VERSION,
+        DEFAULT_STACK_FRAME_SIZE,
+    },
+    journal::{ExecutionResult, Journal, JOURNAL_VERSION},
+    sysvar::SysvarCache,
+    Pubkey,
+};
+
+mod accounts;
+mod programs;
+mod sysvars;
+mod transaction;
+
+/// Proves and verifies execution of Solana programs inside the RISC Zero zkVM.
+#[derive(Parser)]
+#[command(name = "zksol", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Execute a program in the zkVM and generate a proof of execution
+    Prove {
+        #[command(flatten)]
+        program: ProgramArgs,
+        /// Where to write the receipt
+        #[arg(long, default_value = "receipt.bin")]
+        receipt: PathBuf,
+    },
+    /// Execute a program in the zkVM without generating a proof
+    Execute {
+        #[command(flatten)]
+        program: ProgramArgs,
+    },
+    /// Verify a previously generated receipt
+    Verify {
+        /// Receipt written by `prove`
+        #[arg(default_value = "receipt.bin")]
+        receipt: PathBuf,
+    },
+}
+
+/// Describes the program invocation to run inside the zkVM.
+#[derive(Args)]
+struct ProgramArgs {
+    /// Path to the compiled Solana program (.so)
+    program: PathBuf,
+    /// Base58 program id the program is invoked as [default: derived from the ELF hash]
+    #[arg(long)]
+    program_id: Option<String>,
+    /// JSON file listing the instruction's accounts
+    #[arg(long)]
+    accounts: Option<PathBuf>,
+    /// Account dumped with `solana account --output json`, as `[ADDRESS=]PATH` (repeatable).
+    /// Added read-only after the accounts file's accounts, at ADDRESS when given
+    #[arg(long = "account-dump", value_name = "[ADDRESS=]PATH")]
+    account_dumps: Vec<String>,
+    /// Hex-encoded instruction data
+    #[arg(long, default_value = "")]
+    data: String,
+    /// JSON file holding a transaction message to run instead of a single instruction.
+    /// The accounts file then gives the state of the message's account keys
+    #[arg(long, conflicts_with = "data")]
+    transaction: Option<PathBuf>,
+    /// Directory of programs that can be invoked through CPI: spl_token.so, spl_token_2022.so,
+    /// spl_associated_token_account.so and any `<base58 id>.so`
+    #[arg(long)]
+    program_dir: Option<PathBuf>,
+    /// Program that can be invoked through CPI, as `<base58 id>=<path to .so>` (repeatable,
+    /// overrides --program-dir)
+    #[arg(long = "cpi-program", value_name = "ID=PATH")]
+    cpi_programs: Vec<String>,
+    /// JSON file declaring the sysvars served to the program [default: Solana's defaults]
+    #[arg(long)]
+    sysvars: Option<PathBuf>,
+    /// Slot reported by the Clock sysvar, overriding the sysvars file
+    #[arg(long)]
+    slot: Option<u64>,
+    /// Unix timestamp reported by the Clock sysvar, overriding the sysvars file
+    #[arg(long)]
+    unix_timestamp: Option<i64>,
+    /// Commit the full program logs to the journal, not just their digest
+    #[arg(long)]
+    commit_logs: bool,
+    #[command(flatten)]
+    execution: ExecutionArgs,
+}
+
+/// Compute budget and VM settings, defaulting to Solana's.
+#[derive(Args)]
+struct ExecutionArgs {
+    /// Compute units available to the program
+    #[arg(long, default_value_t = DEFAULT_COMPUTE_UNIT_LIMIT)]
+    compute_unit_limit: u64,
+    /// Requested heap frame in bytes, a multiple of 1024 from 32 KiB to 256 KiB
+    #[arg(long, default_value_t = DEFAULT_HEAP_SIZE)]
+    heap_size: u32,
+    /// Maximum depth of SBPF-to-SBPF calls
+    #[arg(long, default_value_t = DEFAULT_MAX_CALL_DEPTH)]
+    max_call_depth: u32,
+    /// Size of a single stack frame in bytes
+    #[arg(long, default_value_t = DEFAULT_STACK_FRAME_SIZE)]
+    stack_frame_size: u32,
+    /// Oldest SBPF version the loader accepts
+    #[arg(long, default_value_t = DEFAULT_MIN_SBPF_VERSION)]
+    min_sbpf_version: u8,
+    /// Newest SBPF version the loader accepts
+    #[arg(long, default_value_t = DEFAULT_MAX_SBPF_VERSION)]
+    max_sbpf_version: u8,
+}
+
+impl From<&ExecutionArgs> for ExecutionParameters {
+    fn from(args: &ExecutionArgs) -> Self {
+        ExecutionParameters {
+            compute_unit_limit: args.compute_unit_limit,
+            heap_size: args.heap_size,
+            max_call_depth: args.max_call_depth,
+            stack_frame_size: args.stack_frame_size,
+            min_sbpf_version: args.min_sbpf_version,
+            max_sbpf_version: args.max_sbpf_version,
+        }
+    }
+}
 
 /// Host application that generates zero-knowledge proofs for Solana program execution.
 /// Loads a compiled Solana BPF program (.so file) and executes it inside the RISC Zero zkVM,
 /// producing a verifiable proof of correct execution.
-fn main() {
+fn main() -> Result<()> {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
         .init();
 
+    match Cli::parse().command {
+        Command::Prove { program, receipt } => prove(&program, &receipt),
+        Command::Execute { program } => execute(&program),
+        Command::Verify { receipt } => verify(&receipt),
+    }
+}
+
+/// Loads the program bytecode and the input envelope described by the command line.
+fn load_input(args: &ProgramArgs) -> Result<(Vec<u8>, Input)> {
     // Load compiled Solana BPF program bytecode
-    let bytecode =
-        fs::read("minimal-sol/target/deploy/minimal_sol.so").expect("Failed to read bytecode");
+    let bytecode = fs::read(&args.program)
+        .with_context(|| format!("Failed to read bytecode {}", args.program.display()))?;
+    let program_id = args
+        .program_id
+        .clone()
+        .map(Pubkey::try_from)
+        .transpose()
+        .map_err(|e| anyhow!(e))?;
+    let mut accounts = match &args.accounts {
+        Some(path) => accounts::load_accounts(path)?,
+        None => vec![],
+    };
+    for spec in &args.account_dumps {
+        let account = accounts::load_account_dump(spec)?;
+        if accounts
+            .iter()
+            .any(|existing| existing.pubkey == account.pubkey)
+        {
+            bail!("Account {} is loaded twice", account.pubkey);
+        }
+        accounts.push(account);
+    }
+    let message = args
+        .transaction
+        .as_deref()
+        .map(transaction::load_message)
+        .transpose()?;
+    if let Some(message) = &message {
+        accounts = transaction::transaction_accounts(message, accounts)?;
+    }
+    let instruction_data = hex::decode(&args.data).context("Invalid hex instruction data")?;
+    let mut registry = programs::ProgramRegistry::default();
+    if let Some(dir) = &args.program_dir {
+        registry.load_dir(dir)?;
+    }
+    for spec in &args.cpi_programs {
+        registry.load_spec(spec)?;
+    }
+    let programs = registry.into_programs();
+    let parameters = ExecutionParameters::from(&args.execution);
+    parameters.validate().map_err(|e| anyhow!(e))?;
+    let mut sysvars = match &args.sysvars {
+        Some(path) => sysvars::load_sysvars(path)?,
+        None => SysvarCache::default(),
+    };
+    if let Some(slot) = args.slot {
+        sysvars.clock.slot = slot;
+    }
+    if let Some(unix_timestamp) = args.unix_timestamp {
+        sysvars.clock.unix_timestamp = unix_timestamp;
+    }
+
+    match &program_id {
+        Some(program_id) => println!("Program: {} ({})", args.program.display(), program_id),
+        None => println!("Program: {}", args.program.display()),
+    }
+    if let Some(message) = &message {
+        println!("  Transaction: {} instructions", message.instructions.len());
+    }
+    println!("  Accounts: {}", accounts.len());
+    println!("  Instruction data: {} bytes", instruction_data.len());
+    println!("  Slot: {}", sysvars.clock.slot);
+    for program in &programs {
+        println!("  CPI program: {}", program.program_id);
+    }
+
+    Ok((
+        bytecode,
+        Input {
+            program_id,
+            accounts,
+            instruction_data,
+            parameters,
+            sysvars,
+            programs,
+            message,
+            commit_logs: args.commit_logs,
+        },
+    ))
+}
 
-    // Create zkVM execution environment with Solana bytecode as input
-    let env = ExecutorEnv::builder()
-        .write(&bytecode)
-        .unwrap()
+/// Builds the zkVM execution environment holding the program bytecode and its inputs.
+fn build_env<'a>(bytecode: &[u8], input: &Input) -> Result<ExecutorEnv<'a>> {
+    // Create zkVM execution environment with Solana bytecode and inputs
+    ExecutorEnv::builder()
+        .write(&bytecode)?
+        .write(input)?
         .build()
-        .unwrap();
+}
 
+/// Executes the Solana program in the zkVM and writes the resulting receipt to disk.
+fn prove(args: &ProgramArgs, receipt_path: &Path) -> Result<()> {
+    let (bytecode, input) = load_input(args)?;
+    let env = build_env(&bytecode, &input)?;
     let prover = default_prover();
 
     // Execute the Solana program in zkVM and generate proof
     println!("Generating proof of Solana program execution...");
-    let prove_info = prover.prove(env, SOL_ELF).unwrap();
+    let prove_info = prover.prove(env, SOL_ELF)?;
 
     // Display execution statistics
     println!("Proof generated successfully!");
     println!("  Total cycles: {}", prove_info.stats.total_cycles);
     println!("  User cycles: {}", prove_info.stats.user_cycles);
 
-    // Extract execution result from proof journal
     let receipt = prove_info.receipt;
-    let executed_successfully: bool = receipt.journal.decode().unwrap();
-    if executed_successfully {
-        println!("Solana program executed successfully!");
-    } else {
-        println!("Error - Solana program failed execution");
-    }
+    let journal: Journal = receipt.journal.decode()?;
+    report(&journal)?;
+    accounts::print_state_diff(&input.accounts, &journal.output_accounts);
 
     // Cryptographically verify the proof is valid for this specific program
     println!("\nVerifying proof...");
-    receipt.verify(SOL_ID).unwrap();
+    receipt.verify(SOL_ID)?;
+    println!("Proof verified!");
+
+    fs::write(receipt_path, bincode::serialize(&receipt)?)
+        .with_context(|| format!("Failed to write receipt {}", receipt_path.display()))?;
+    println!("Receipt written to {}", receipt_path.display());
+    Ok(())
+}
+
+/// Executes the Solana program in the zkVM without proving, for fast iteration.
+fn execute(args: &ProgramArgs) -> Result<()> {
+    let (bytecode, input) = load_input(args)?;
+    let env = build_env(&bytecode, &input)?;
+    let executor = default_executor();
+
+    println!("Executing Solana program...");
+    let session = executor.execute(env, SOL_ELF)?;
+    println!("  Total cycles: {}", session.cycles());
+
+    let journal: Journal = session.journal.decode()?;
+    report(&journal)?;
+    accounts::print_state_diff(&input.accounts, &journal.output_accounts);
+    Ok(())
+}
+
+/// Verifies a receipt against the guest image id and reports its journal.
+fn verify(receipt_path: &Path) -> Result<()> {
+    let bytes = fs::read(receipt_path)
+        .with_context(|| format!("Failed to read receipt {}", receipt_path.display()))?;
+    let receipt: Receipt = bincode::deserialize(&bytes).context("Invalid receipt")?;
+
+    println!("Verifying proof...");
+    receipt.verify(SOL_ID)?;
     println!("Proof verified!");
+
+    let journal: Journal = receipt.journal.decode()?;
+    report(&journal)?;
+    accounts::print_accounts(&journal.output_accounts);
+    Ok(())
+}
+
+/// Prints the execution summary committed to the journal.
+fn report(journal: &Journal) -> Result<()> {
+    if journal.version != JOURNAL_VERSION {
+        bail!(
+            "Unsupported journal version {} (expected {})",
+            journal.version,
+            JOURNAL_VERSION
+        );
+    }
+
+    match &journal.result {
+        ExecutionResult::Returned(0) => println!("Solana program executed successfully!"),
+        ExecutionResult::Returned(code) => {
+            println!("Error - Solana program returned error code {:#x}", code)
+        }
+        ExecutionResult::Aborted(reason) => {
+            println!("Error - Solana program failed execution: {}", reason)
+        }
+        ExecutionResult::Rejected(reason) => {
+            println!("Error - Runtime rejected account changes: {}", reason)
+        }
+    }
+    println!("  Program id: {}", journal.program_id);
+    println!("  Program hash: {}", hex::encode(journal.program_hash));
+    println!(
+        "  Instruction data hash: {}",
+        hex::encode(journal.instruction_data_hash)
+    );
+    if let Some(message_hash) = journal.message_hash {
+        println!("  Message hash: {}", hex::encode(message_hash));
+    }
+    println!(
+        "  Input accounts hash: {}",
+        hex::encode(journal.input_accounts_hash)
+    );
+    println!(
+        "  Output accounts hash: {}",
+        hex::encode(journal.output_accounts_hash)
+    );
+    println!(
+        "  Compute units consumed: {} of {}",
+        journal.compute_units_consumed, journal.parameters.compute_unit_limit
+    );
+    println!("  Heap size: {} bytes", journal.parameters.heap_size);
+    println!("  Instruction count: {}", journal.instruction_count);
+    sysvars::print_sysvars(&journal.sysvars);
+    for program in &journal.programs {
+        println!(
+            "  CPI program: {} (hash {})",
+            program.program_id,
+            hex::encode(program.program_hash)
+        );
+    }
+    println!("  Log digest: {}", hex::encode(journal.log_digest));
+    if let Some(return_data) = &journal.return_data {
+        println!(
+            "  Return data: {} (set by {})",
+            hex::encode(&return_data.data),
+            return_data.program_id
+        );
+    }
+    if let Some(logs) = &journal.logs {
+        println!("Program logs:");
+        for message in logs {
+            println!("  {}", message);
+        }
+    }
+    Ok(())
 }
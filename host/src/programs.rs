@@ -0,0 +1,168 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use anyhow::{anyhow, Context, Result};
+use runtime::{input::Program, Pubkey};
+use std::{fs, path::Path};
+
+/// Well-known programs, found in a program directory under the file names
+/// their releases are published with.
+const KNOWN_PROGRAMS: &[(&str, &str)] = &[
+    (
+        "spl_token.so",
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    ),
+    (
+        "spl_token_2022.so",
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+    ),
+    (
+        "spl_associated_token_account.so",
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    ),
+];
+
+/// Programs sent to the guest as CPI targets, keyed by program id.
+#[derive(Default)]
+pub struct ProgramRegistry {
+    programs: Vec<Program>,
+}
+
+impl ProgramRegistry {
+    /// Registers a program, replacing any ELF already registered for its id.
+    pub fn add(&mut self, program_id: Pubkey, elf: Vec<u8>) {
+        match self
+            .programs
+            .iter_mut()
+            .find(|program| program.program_id == program_id)
+        {
+            Some(program) => program.elf = elf,
+            None => self.programs.push(Program { program_id, elf }),
+        }
+    }
+
+    /// Registers the programs in a directory: the well-known SPL programs under their
+    /// release file names, and any other program saved as `<base58 id>.so`.
+    /// Files are loaded in name order so the same directory always gives the same input.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<()> {
+        let mut paths = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read program directory {}", dir.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Failed to read program directory {}", dir.display()))?;
+        paths.sort();
+
+        for path in paths {
+            if path.extension().and_then(|extension| extension.to_str()) != Some("so") {
+                continue;
+            }
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let program_id = match KNOWN_PROGRAMS.iter().find(|(name, _)| *name == file_name) {
+                Some((_, program_id)) => program_id.to_string(),
+                None => path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into(),
+            };
+            let Ok(program_id) = Pubkey::try_from(program_id) else {
+                println!(
+                    "Skipping {}: not a well-known program or <program id>.so",
+                    path.display()
+                );
+                continue;
+            };
+            let elf = fs::read(&path)
+                .with_context(|| format!("Failed to read bytecode {}", path.display()))?;
+            self.add(program_id, elf);
+        }
+        Ok(())
+    }
+
+    /// Registers a program given as `<base58 id>=<path to .so>`.
+    pub fn load_spec(&mut self, spec: &str) -> Result<()> {
+        let (program_id, path) = spec
+            .split_once('=')
+            .with_context(|| format!("Invalid CPI program '{}', expected ID=PATH", spec))?;
+        let program_id = Pubkey::try_from(program_id.to_string()).map_err(|e| anyhow!(e))?;
+        let elf = fs::read(path).with_context(|| format!("Failed to read bytecode {}", path))?;
+        self.add(program_id, elf);
+        Ok(())
+    }
+
+    /// The registered programs, in the order they were first registered.
+    pub fn into_programs(self) -> Vec<Program> {
+        self.programs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PROGRAM_ID: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+    const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    /// A program directory holding `files`, each written with its name as contents.
+    fn program_dir(name: &str, files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zksol-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            fs::write(dir.join(file), file).unwrap();
+        }
+        dir
+    }
+
+    fn ids(programs: &[Program]) -> Vec<String> {
+        programs
+            .iter()
+            .map(|program| program.program_id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn load_dir() {
+        let program_file = format!("{}.so", PROGRAM_ID);
+        let dir = program_dir(
+            "programs",
+            &[
+                "spl_token.so",
+                &program_file,
+                "README.md",
+                "not-a-program.so",
+            ],
+        );
+        let mut registry = ProgramRegistry::default();
+        registry.load_dir(&dir).unwrap();
+        let programs = registry.into_programs();
+
+        // Sorted by file name, skipping the README and the unparsable name
+        assert_eq!(ids(&programs), [PROGRAM_ID, TOKEN_PROGRAM]);
+        assert_eq!(programs[0].elf, program_file.as_bytes());
+        assert_eq!(programs[1].elf, b"spl_token.so");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn load_spec_overrides_dir() {
+        let dir = program_dir("override", &["spl_token.so", "patched.so"]);
+        let mut registry = ProgramRegistry::default();
+        registry.load_dir(&dir).unwrap();
+        registry
+            .load_spec(&format!(
+                "{}={}",
+                TOKEN_PROGRAM,
+                dir.join("patched.so").display()
+            ))
+            .unwrap();
+        let programs = registry.into_programs();
+
+        assert_eq!(ids(&programs), [TOKEN_PROGRAM]);
+        assert_eq!(programs[0].elf, b"patched.so");
+
+        let mut registry = ProgramRegistry::default();
+        assert!(registry.load_spec("spl_token.so").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
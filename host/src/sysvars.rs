@@ -0,0 +1,28 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use anyhow::{Context, Result};
+use runtime::sysvar::SysvarCache;
+use std::{fs, path::Path};
+
+/// Loads the sysvars from a JSON file. Sysvars and fields that are left out
+/// keep Solana's defaults.
+pub fn load_sysvars(path: &Path) -> Result<SysvarCache> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read sysvars file {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse sysvars file {}", path.display()))
+}
+
+/// Prints the sysvars committed to the journal that time-dependent programs rely on.
+pub fn print_sysvars(sysvars: &SysvarCache) {
+    let clock = &sysvars.clock;
+    println!(
+        "  Clock: slot {}, epoch {}, unix timestamp {}",
+        clock.slot, clock.epoch, clock.unix_timestamp
+    );
+    println!(
+        "  Last restart slot: {}",
+        sysvars.last_restart_slot.last_restart_slot
+    );
+}
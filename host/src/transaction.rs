@@ -0,0 +1,139 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use anyhow::{anyhow, bail, Context, Result};
+use runtime::{
+    transaction::{CompiledInstruction, Message, MessageHeader},
+    Account, Pubkey,
+};
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// Owner of native programs such as the System Program.
+const NATIVE_LOADER_ID: &str = "NativeLoader1111111111111111111111111111111";
+/// Owner of deployed SBPF programs.
+const BPF_LOADER_UPGRADEABLE_ID: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
+
+/// Message as it appears in a transaction file.
+/// Keys and the blockhash are base58 strings and instruction data is hex encoded.
+#[derive(Deserialize)]
+struct MessageEntry {
+    header: MessageHeader,
+    account_keys: Vec<String>,
+    #[serde(default)]
+    recent_blockhash: Option<String>,
+    instructions: Vec<InstructionEntry>,
+}
+
+#[derive(Deserialize)]
+struct InstructionEntry {
+    program_id_index: u8,
+    #[serde(default)]
+    accounts: Vec<u8>,
+    #[serde(default)]
+    data: String,
+}
+
+impl TryFrom<MessageEntry> for Message {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: MessageEntry) -> Result<Self> {
+        let recent_blockhash = match entry.recent_blockhash {
+            Some(blockhash) => Pubkey::try_from(blockhash)
+                .map_err(|e| anyhow!(e))
+                .context("Invalid recent blockhash")?
+                .as_ref()
+                .try_into()?,
+            None => [0; 32],
+        };
+        Ok(Message {
+            header: entry.header,
+            account_keys: entry
+                .account_keys
+                .into_iter()
+                .map(|key| Pubkey::try_from(key).map_err(|e| anyhow!(e)))
+                .collect::<Result<_>>()?,
+            recent_blockhash,
+            instructions: entry
+                .instructions
+                .into_iter()
+                .enumerate()
+                .map(|(index, instruction)| {
+                    Ok(CompiledInstruction {
+                        program_id_index: instruction.program_id_index,
+                        accounts: instruction.accounts,
+                        data: hex::decode(&instruction.data).with_context(|| {
+                            format!("Invalid hex data in instruction {}", index)
+                        })?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Loads a transaction message from a JSON file and checks it is well formed.
+pub fn load_message(path: &Path) -> Result<Message> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transaction file {}", path.display()))?;
+    let entry: MessageEntry = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse transaction file {}", path.display()))?;
+    let message = Message::try_from(entry)?;
+    message
+        .sanitize()
+        .map_err(|e| anyhow!(e))
+        .with_context(|| format!("Invalid transaction {}", path.display()))?;
+    Ok(message)
+}
+
+/// Orders the accounts by the message's account keys, with the privileges the header
+/// grants. Keys missing from the accounts file start out empty: invoked programs as
+/// executable accounts of their loader, anything else as a new System Program account.
+pub fn transaction_accounts(message: &Message, accounts: Vec<Account>) -> Result<Vec<Account>> {
+    if let Some(account) = accounts
+        .iter()
+        .find(|account| !message.account_keys.contains(&account.pubkey))
+    {
+        bail!("Account {} is not in the transaction", account.pubkey);
+    }
+
+    let system_program = Pubkey::default();
+    let native_loader = Pubkey::try_from(NATIVE_LOADER_ID.to_string()).map_err(|e| anyhow!(e))?;
+    let bpf_loader =
+        Pubkey::try_from(BPF_LOADER_UPGRADEABLE_ID.to_string()).map_err(|e| anyhow!(e))?;
+    Ok(message
+        .account_keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| {
+            let account = accounts
+                .iter()
+                .find(|account| account.pubkey == *key)
+                .cloned()
+                .unwrap_or_else(|| {
+                    let (owner, executable) = if *key == system_program {
+                        (native_loader, true)
+                    } else if message.is_invoked(index) {
+                        (bpf_loader, true)
+                    } else {
+                        (system_program, false)
+                    };
+                    Account {
+                        pubkey: *key,
+                        is_signer: false,
+                        is_writable: false,
+                        lamports: 0,
+                        data: vec![],
+                        owner,
+                        executable,
+                        rent_epoch: 0,
+                    }
+                });
+            Account {
+                is_signer: message.is_signer(index),
+                is_writable: message.is_writable(index),
+                ..account
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,314 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use runtime::{Account, Pubkey};
+
+/// Checks the account changes made by a program against Solana's runtime rules.
+/// Mirrors the pre/post account verification the bank performs after each instruction,
+/// so a successful proof never attests to a transition the chain would refuse.
+/// Data growth is bounded separately when the accounts are deserialized.
+pub fn verify_account_changes(
+    program_id: &Pubkey,
+    pre_accounts: &[Account],
+    post_accounts: &[Account],
+) -> Result<(), String> {
+    let mut pre_total: u128 = 0;
+    let mut post_total: u128 = 0;
+    for (index, (pre, post)) in pre_accounts.iter().zip(post_accounts).enumerate() {
+        // Duplicates share their state with the first occurrence of the key
+        if pre_accounts[..index].iter().any(|a| a.pubkey == pre.pubkey) {
+            continue;
+        }
+        let is_writable = pre_accounts
+            .iter()
+            .any(|a| a.pubkey == pre.pubkey && a.is_writable);
+        verify_account(program_id, pre, post, is_writable)?;
+
+        pre_total += pre.lamports as u128;
+        post_total += post.lamports as u128;
+    }
+
+    // Lamports may move between accounts but never be created or destroyed
+    if pre_total != post_total {
+        return Err(format!(
+            "UnbalancedInstruction: {} lamports before, {} after",
+            pre_total, post_total
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that every account passed more than once carries the same state each time,
+/// since the program only ever sees the first occurrence.
+pub fn verify_duplicate_accounts(accounts: &[Account]) -> Result<(), String> {
+    for (index, account) in accounts.iter().enumerate() {
+        if let Some(original) = accounts[..index]
+            .iter()
+            .find(|a| a.pubkey == account.pubkey)
+        {
+            if original.lamports != account.lamports
+                || original.data != account.data
+                || original.owner != account.owner
+                || original.executable != account.executable
+                || original.rent_epoch != account.rent_epoch
+            {
+                return Err(format!(
+                    "Duplicate account {} at index {} differs from its first occurrence",
+                    account.pubkey, index
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verifies the changes to a single account.
+pub fn verify_account(
+    program_id: &Pubkey,
+    pre: &Account,
+    post: &Account,
+    is_writable: bool,
+) -> Result<(), String> {
+    let owned = *program_id == pre.owner;
+    let violation = |error: &str| Err(format!("{}: account {}", error, pre.pubkey));
+
+    // Only the owner may assign a writable, non-executable account to a new owner,
+    // and only once its data has been cleared
+    if pre.owner != post.owner
+        && (!is_writable || pre.executable || !owned || !is_zeroed(&post.data))
+    {
+        return violation("ModifiedProgramId");
+    }
+
+    // An account not owned by the program can be credited but never debited
+    if !owned && pre.lamports > post.lamports {
+        return violation("ExternalAccountLamportSpend");
+    }
+
+    if pre.lamports != post.lamports {
+        if !is_writable {
+            return violation("ReadonlyLamportChange");
+        }
+        if pre.executable {
+            return violation("ExecutableLamportChange");
+        }
+    }
+
+    // Only the owner of a writable, non-executable account may resize it
+    if pre.data.len() != post.data.len() && (!is_writable || pre.executable || !owned) {
+        return violation("AccountDataSizeChanged");
+    }
+
+    // Only the owner of a writable, non-executable account may change its data
+    if !(owned && is_writable && !pre.executable) && pre.data != post.data {
+        return if pre.executable {
+            violation("ExecutableDataModified")
+        } else if is_writable {
+            violation("ExternalAccountDataModified")
+        } else {
+            violation("ReadonlyDataModified")
+        };
+    }
+
+    // The owner of a writable account may mark it executable, but never undo it
+    if pre.executable != post.executable && (pre.executable || !is_writable || !owned) {
+        return violation("ExecutableModified");
+    }
+
+    Ok(())
+}
+
+fn is_zeroed(data: &[u8]) -> bool {
+    data.iter().all(|b| *b == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program_id() -> Pubkey {
+        Pubkey::from([1; 32])
+    }
+
+    fn account(seed: u8, owner: Pubkey, is_writable: bool) -> Account {
+        Account {
+            pubkey: Pubkey::from([seed; 32]),
+            is_signer: false,
+            is_writable,
+            lamports: 100,
+            data: vec![1, 2, 3],
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn assert_violation(result: Result<(), String>, error: &str) {
+        let message = result.expect_err("change should be rejected");
+        assert!(message.starts_with(error), "{}", message);
+    }
+
+    #[test]
+    fn readonly_account_writes() {
+        let pre = account(2, program_id(), false);
+
+        let credited = Account {
+            lamports: 101,
+            ..pre.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &pre, &credited, false),
+            "ReadonlyLamportChange",
+        );
+
+        let modified = Account {
+            data: vec![1, 2, 4],
+            ..pre.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &pre, &modified, false),
+            "ReadonlyDataModified",
+        );
+    }
+
+    #[test]
+    fn debit_of_account_owned_by_another_program() {
+        let pre = account(2, Pubkey::from([9; 32]), true);
+
+        let debited = Account {
+            lamports: 99,
+            ..pre.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &pre, &debited, true),
+            "ExternalAccountLamportSpend",
+        );
+
+        // Crediting is allowed
+        let credited = Account {
+            lamports: 101,
+            ..pre.clone()
+        };
+        assert_eq!(verify_account(&program_id(), &pre, &credited, true), Ok(()));
+    }
+
+    #[test]
+    fn unbalanced_lamports() {
+        let pre = vec![
+            account(2, program_id(), true),
+            account(3, Pubkey::from([9; 32]), true),
+        ];
+
+        let mut transfer = pre.clone();
+        transfer[0].lamports -= 40;
+        transfer[1].lamports += 40;
+        assert_eq!(
+            verify_account_changes(&program_id(), &pre, &transfer),
+            Ok(())
+        );
+
+        let mut minted = pre.clone();
+        minted[1].lamports += 40;
+        assert_violation(
+            verify_account_changes(&program_id(), &pre, &minted),
+            "UnbalancedInstruction",
+        );
+    }
+
+    #[test]
+    fn owner_change_requires_zeroed_data() {
+        let pre = account(2, program_id(), true);
+        let new_owner = Pubkey::from([9; 32]);
+
+        let assigned = Account {
+            owner: new_owner,
+            ..pre.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &pre, &assigned, true),
+            "ModifiedProgramId",
+        );
+
+        let cleared = Account {
+            owner: new_owner,
+            data: vec![0; 3],
+            ..pre.clone()
+        };
+        assert_eq!(verify_account(&program_id(), &pre, &cleared, true), Ok(()));
+    }
+
+    #[test]
+    fn executable_changes() {
+        let program = Account {
+            executable: true,
+            ..account(2, program_id(), true)
+        };
+
+        let credited = Account {
+            lamports: 101,
+            ..program.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &program, &credited, true),
+            "ExecutableLamportChange",
+        );
+
+        let modified = Account {
+            data: vec![1, 2, 4],
+            ..program.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &program, &modified, true),
+            "ExecutableDataModified",
+        );
+
+        let unmarked = Account {
+            executable: false,
+            ..program.clone()
+        };
+        assert_violation(
+            verify_account(&program_id(), &program, &unmarked, true),
+            "ExecutableModified",
+        );
+
+        // The owner may mark a writable account executable
+        let pre = account(3, program_id(), true);
+        let marked = Account {
+            executable: true,
+            ..pre.clone()
+        };
+        assert_eq!(verify_account(&program_id(), &pre, &marked, true), Ok(()));
+        assert_violation(
+            verify_account(&program_id(), &pre, &marked, false),
+            "ExecutableModified",
+        );
+    }
+
+    #[test]
+    fn inconsistent_duplicates() {
+        let original = account(2, program_id(), false);
+        let duplicate = Account {
+            is_writable: true,
+            ..original.clone()
+        };
+        // Flags may differ between occurrences, state may not
+        assert_eq!(
+            verify_duplicate_accounts(&[original.clone(), duplicate.clone()]),
+            Ok(())
+        );
+
+        let diverged = Account {
+            lamports: 50,
+            ..duplicate.clone()
+        };
+        assert!(verify_duplicate_accounts(&[original.clone(), diverged]).is_err());
+
+        // A key writable through any occurrence may be written through the first
+        let pre = vec![original, duplicate];
+        let mut post = pre.clone();
+        post[0].data = vec![4, 5, 6];
+        post[1].data = vec![4, 5, 6];
+        assert_eq!(verify_account_changes(&program_id(), &pre, &post), Ok(()));
+    }
+}
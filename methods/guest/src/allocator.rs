@@ -0,0 +1,47 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use solana_sbpf::ebpf::MM_HEAP_START;
+
+/// Bump allocator over the program heap, as used by Solana's deprecated
+/// sol_alloc_free_ syscall. Allocations are never freed.
+#[derive(Debug, Default)]
+pub struct BpfAllocator {
+    len: u64,
+    pos: u64,
+}
+
+impl BpfAllocator {
+    /// Creates an allocator over a heap of `len` bytes.
+    pub fn new(len: u64) -> Self {
+        Self { len, pos: 0 }
+    }
+
+    /// Returns the VM address of `size` bytes aligned to `align`, or `None` once the heap is exhausted.
+    pub fn alloc(&mut self, size: u64, align: u64) -> Option<u64> {
+        let bytes_to_align = (align - self.pos % align) % align;
+        if self.pos.saturating_add(bytes_to_align).saturating_add(size) > self.len {
+            return None;
+        }
+        self.pos = self.pos.saturating_add(bytes_to_align);
+        let addr = MM_HEAP_START.saturating_add(self.pos);
+        self.pos = self.pos.saturating_add(size);
+        Some(addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump_allocation() {
+        let mut allocator = BpfAllocator::new(64);
+        assert_eq!(allocator.alloc(3, 8), Some(MM_HEAP_START));
+        assert_eq!(allocator.alloc(8, 8), Some(MM_HEAP_START + 8));
+        assert_eq!(allocator.alloc(1, 1), Some(MM_HEAP_START + 16));
+        assert_eq!(allocator.alloc(48, 8), None);
+        assert_eq!(allocator.alloc(40, 8), Some(MM_HEAP_START + 24));
+        assert_eq!(allocator.alloc(1, 1), None);
+    }
+}
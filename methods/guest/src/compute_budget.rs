@@ -0,0 +1,79 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Compute unit costs charged by syscalls, matching Solana's default compute budget.
+
+/// Base cost of a syscall that does not have a more specific cost.
+pub const SYSCALL_BASE_COST: u64 = 100;
+/// Number of bytes copied per compute unit when moving data across the syscall boundary.
+pub const CPI_BYTES_PER_UNIT: u64 = 250;
+/// Cost of logging five u64 values with sol_log_64_.
+pub const LOG_64_UNITS: u64 = 100;
+/// Cost of logging a public key with sol_log_pubkey.
+pub const LOG_PUBKEY_UNITS: u64 = 100;
+/// Minimum cost of a memory operation.
+pub const MEM_OP_BASE_COST: u64 = 10;
+/// Base cost of the hashing syscalls.
+pub const SHA256_BASE_COST: u64 = 85;
+/// Cost per two bytes hashed.
+pub const SHA256_BYTE_COST: u64 = 1;
+/// Maximum number of slices a hashing syscall accepts.
+pub const SHA256_MAX_SLICES: u64 = 20_000;
+/// Base cost of reading a sysvar.
+pub const SYSVAR_BASE_COST: u64 = 100;
+/// Cost of deriving one program address, charged again for every bump seed tried.
+pub const CREATE_PROGRAM_ADDRESS_UNITS: u64 = 1_500;
+/// Base cost of a cross-program invocation.
+pub const INVOKE_UNITS: u64 = 1_000;
+/// Cost of a System Program instruction.
+pub const SYSTEM_PROGRAM_UNITS: u64 = 150;
+/// Cost of recovering a secp256k1 public key.
+pub const SECP256K1_RECOVER_COST: u64 = 25_000;
+/// Cost of adding two alt_bn128 G1 points.
+pub const ALT_BN128_ADDITION_COST: u64 = 334;
+/// Cost of multiplying an alt_bn128 G1 point by a scalar.
+pub const ALT_BN128_MULTIPLICATION_COST: u64 = 3_840;
+/// Cost of the first pair in an alt_bn128 pairing check.
+pub const ALT_BN128_PAIRING_ONE_PAIR_COST_FIRST: u64 = 36_364;
+/// Cost of every further pair in an alt_bn128 pairing check.
+pub const ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER: u64 = 12_121;
+/// Cost of compressing an alt_bn128 G1 point.
+pub const ALT_BN128_G1_COMPRESS_COST: u64 = 30;
+/// Cost of decompressing an alt_bn128 G1 point.
+pub const ALT_BN128_G1_DECOMPRESS_COST: u64 = 398;
+/// Cost of compressing an alt_bn128 G2 point.
+pub const ALT_BN128_G2_COMPRESS_COST: u64 = 86;
+/// Cost of decompressing an alt_bn128 G2 point.
+pub const ALT_BN128_G2_DECOMPRESS_COST: u64 = 13_610;
+/// Cost of validating a curve25519 Edwards point.
+pub const CURVE25519_EDWARDS_VALIDATE_POINT_COST: u64 = 159;
+/// Cost of adding two curve25519 Edwards points.
+pub const CURVE25519_EDWARDS_ADD_COST: u64 = 473;
+/// Cost of subtracting two curve25519 Edwards points.
+pub const CURVE25519_EDWARDS_SUBTRACT_COST: u64 = 475;
+/// Cost of multiplying a curve25519 Edwards point by a scalar.
+pub const CURVE25519_EDWARDS_MULTIPLY_COST: u64 = 2_177;
+/// Base cost of a curve25519 Edwards multiscalar multiplication.
+pub const CURVE25519_EDWARDS_MSM_BASE_COST: u64 = 2_273;
+/// Cost of every point after the first in a curve25519 Edwards multiscalar multiplication.
+pub const CURVE25519_EDWARDS_MSM_INCREMENTAL_COST: u64 = 758;
+/// Cost of validating a curve25519 Ristretto point.
+pub const CURVE25519_RISTRETTO_VALIDATE_POINT_COST: u64 = 169;
+/// Cost of adding two curve25519 Ristretto points.
+pub const CURVE25519_RISTRETTO_ADD_COST: u64 = 521;
+/// Cost of subtracting two curve25519 Ristretto points.
+pub const CURVE25519_RISTRETTO_SUBTRACT_COST: u64 = 519;
+/// Cost of multiplying a curve25519 Ristretto point by a scalar.
+pub const CURVE25519_RISTRETTO_MULTIPLY_COST: u64 = 2_208;
+/// Base cost of a curve25519 Ristretto multiscalar multiplication.
+pub const CURVE25519_RISTRETTO_MSM_BASE_COST: u64 = 2_303;
+/// Cost of every point after the first in a curve25519 Ristretto multiscalar multiplication.
+pub const CURVE25519_RISTRETTO_MSM_INCREMENTAL_COST: u64 = 788;
+/// Quadratic coefficient of the Poseidon cost, `a * inputs^2 + c`.
+pub const POSEIDON_COST_COEFFICIENT_A: u64 = 61;
+/// Constant term of the Poseidon cost, `a * inputs^2 + c`.
+pub const POSEIDON_COST_COEFFICIENT_C: u64 = 542;
+/// Base cost of a big modular exponentiation.
+pub const BIG_MODULAR_EXPONENTIATION_BASE_COST: u64 = 190;
+/// Divisor of the squared input length charged on top of the base cost.
+pub const BIG_MODULAR_EXPONENTIATION_COST_DIVISOR: u64 = 2;
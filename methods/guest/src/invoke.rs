@@ -0,0 +1,312 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Runs programs on the invoke stack. The top-level program and every program it
+//! reaches through cross-program invocation execute in their own SBPF VM, sharing
+//! the compute meter, logs and account state held by `SolanaContext`.
+
+use crate::{
+    account_rules,
+    allocator::BpfAllocator,
+    compute_budget::SYSTEM_PROGRAM_UNITS,
+    serializer::{SerializedAccount, Serializer, VmSerializedAccount},
+    system_program, SolanaContext,
+};
+use runtime::{journal::ExecutionResult, Account, Pubkey};
+use solana_sbpf::{
+    aligned_memory::AlignedMemory,
+    ebpf::{HOST_ALIGN, MM_HEAP_START, MM_STACK_START},
+    elf::Executable,
+    error::StableResult,
+    memory_region::{MemoryMapping, MemoryRegion},
+    vm::EbpfVm,
+};
+use std::sync::Arc;
+
+/// Deepest the invoke stack may grow: the top-level program and four nested invocations.
+pub const MAX_INVOKE_STACK_HEIGHT: usize = 5;
+
+/// A program on the invoke stack together with the accounts it was given.
+pub struct InvokeFrame {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Account>,
+    pub serialized_accounts: Vec<SerializedAccount>,
+}
+
+impl InvokeFrame {
+    /// Whether any occurrence of the account is writable in this frame.
+    pub fn is_writable(&self, pubkey: &Pubkey) -> bool {
+        self.accounts
+            .iter()
+            .any(|account| account.pubkey == *pubkey && account.is_writable)
+    }
+
+    /// Whether any occurrence of the account is a signer in this frame.
+    pub fn is_signer(&self, pubkey: &Pubkey) -> bool {
+        self.accounts
+            .iter()
+            .any(|account| account.pubkey == *pubkey && account.is_signer)
+    }
+
+    /// Whether the account was passed to this frame at all.
+    pub fn contains(&self, pubkey: &Pubkey) -> bool {
+        self.accounts
+            .iter()
+            .any(|account| account.pubkey == *pubkey)
+    }
+
+    /// Where the account is serialized in this frame's input region.
+    pub fn serialized_account(&self, pubkey: &Pubkey) -> Option<&VmSerializedAccount> {
+        let index = self
+            .accounts
+            .iter()
+            .position(|account| account.pubkey == *pubkey)?;
+        match &self.serialized_accounts[index] {
+            SerializedAccount::Account(serialized) => Some(serialized),
+            SerializedAccount::Duplicate(_) => None,
+        }
+    }
+}
+
+impl SolanaContext {
+    /// The latest known state of an account in the transaction.
+    pub fn account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.transaction_accounts
+            .iter()
+            .find(|account| account.pubkey == *pubkey)
+    }
+
+    /// Records the state of accounts changed by a program.
+    pub fn update_accounts(&mut self, accounts: &[Account]) {
+        for account in accounts {
+            match self
+                .transaction_accounts
+                .iter_mut()
+                .find(|existing| existing.pubkey == account.pubkey)
+            {
+                Some(existing) => {
+                    existing.lamports = account.lamports;
+                    existing.data.clone_from(&account.data);
+                    existing.owner = account.owner;
+                }
+                None => self.transaction_accounts.push(account.clone()),
+            }
+        }
+    }
+
+    /// Loads a program from the input with the loader holding the registered syscalls.
+    /// Each ELF is parsed and verified once; later invocations reuse the executable.
+    pub fn load_program(
+        &mut self,
+        program_id: &Pubkey,
+    ) -> Result<Arc<Executable<SolanaContext>>, String> {
+        if let Some((_, executable)) = self.executables.iter().find(|(id, _)| id == program_id) {
+            return Ok(executable.clone());
+        }
+        let program = self
+            .programs
+            .iter()
+            .find(|program| program.program_id == *program_id)
+            .ok_or_else(|| format!("Unknown program {}", program_id))?;
+        let loader = self
+            .loader
+            .clone()
+            .ok_or("No loader to load programs with")?;
+        let executable = Arc::new(
+            Executable::from_elf(&program.elf, loader)
+                .map_err(|e| format!("Failed to load program {}: {:?}", program_id, e))?,
+        );
+        self.executables.push((*program_id, executable.clone()));
+        Ok(executable)
+    }
+
+    /// The instruction accounts with the latest known state, keeping their own flags.
+    fn synced_accounts(&self, accounts: &[Account]) -> Vec<Account> {
+        accounts
+            .iter()
+            .map(|account| match self.account(&account.pubkey) {
+                Some(state) => Account {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                    ..state.clone()
+                },
+                None => account.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Runs a program over its instruction accounts in a fresh VM, pushed onto the invoke stack.
+/// Returns the instruction count, the result and the accounts as the program left them.
+/// The accounts come back unchanged unless the program succeeded and its changes
+/// since the last cross-program invocation follow Solana's account rules.
+pub fn execute_program(
+    context: &mut SolanaContext,
+    executable: &Executable<SolanaContext>,
+    program_id: Pubkey,
+    accounts: Vec<Account>,
+    instruction_data: &[u8],
+) -> (u64, ExecutionResult, Vec<Account>) {
+    context
+        .log_collector
+        .program_invoke(&program_id, context.invoke_stack.len() + 1);
+    let compute_units_available = context.compute_units_remaining;
+
+    // Serialize input parameters in Solana's format
+    let (parameters, parameter_regions, serialized_accounts) =
+        match Serializer::serialize_parameters(accounts.clone(), instruction_data, program_id) {
+            Ok(serialized) => serialized,
+            Err(e) => {
+                let result = ExecutionResult::Aborted(e);
+                context.log_collector.program_failure(&program_id, &result);
+                return (0, result, accounts);
+            }
+        };
+
+    let sbpf_version = executable.get_sbpf_version();
+    let config = executable.get_config();
+
+    // Allocate stack memory for VM execution
+    let mut stack = AlignedMemory::<HOST_ALIGN>::zero_filled(config.stack_size());
+    let stack_len = stack.len();
+
+    // Allocate the requested heap frame
+    let mut heap = AlignedMemory::<HOST_ALIGN>::zero_filled(context.heap_size as usize);
+
+    // Build memory regions for the VM (code, stack, heap, input)
+    let regions: Vec<MemoryRegion> = vec![
+        executable.get_ro_region(),
+        MemoryRegion::new_writable_gapped(
+            stack.as_slice_mut(),
+            MM_STACK_START,
+            if !sbpf_version.dynamic_stack_frames() && config.enable_stack_frame_gaps {
+                config.stack_frame_size as u64
+            } else {
+                0
+            },
+        ),
+        MemoryRegion::new_writable(heap.as_slice_mut(), MM_HEAP_START),
+    ]
+    .into_iter()
+    .chain(parameter_regions)
+    .collect();
+
+    // Create memory mapping for VM
+    let memory_mapping = match MemoryMapping::new(regions, config, sbpf_version) {
+        Ok(m) => m,
+        Err(e) => panic!("Failed to create memory regions: {:?}", e),
+    };
+
+    // Syscalls see the invoked program and its heap until it returns
+    let caller_program_id = std::mem::replace(&mut context.program_id, program_id);
+    let caller_allocator = std::mem::replace(
+        &mut context.allocator,
+        BpfAllocator::new(context.heap_size as u64),
+    );
+    context.invoke_stack.push(InvokeFrame {
+        program_id,
+        accounts: accounts.clone(),
+        serialized_accounts,
+    });
+
+    // Create the SBPF virtual machine and execute the program
+    let mut vm = EbpfVm::new(
+        executable.get_loader().clone(),
+        sbpf_version,
+        context,
+        memory_mapping,
+        stack_len,
+    );
+    let (instruction_count, result) = vm.execute_program(executable, true);
+    drop(vm);
+
+    let frame = context
+        .invoke_stack
+        .pop()
+        .expect("invoked program is on the invoke stack");
+    context.program_id = caller_program_id;
+    context.allocator = caller_allocator;
+
+    // Read back the account state left in the input region and hold it to Solana's
+    // account rules. As on chain, a failed instruction leaves every account untouched.
+    let (result, output_accounts) = match result {
+        StableResult::Ok(0) => match Serializer::deserialize_parameters(
+            parameters.as_slice(),
+            &accounts,
+            &frame.serialized_accounts,
+        )
+        .and_then(|output_accounts| {
+            let synced_accounts = context.synced_accounts(&accounts);
+            account_rules::verify_account_changes(&program_id, &synced_accounts, &output_accounts)?;
+            Ok(output_accounts)
+        }) {
+            Ok(output_accounts) => {
+                context.update_accounts(&output_accounts);
+                (ExecutionResult::Returned(0), output_accounts)
+            }
+            Err(e) => (ExecutionResult::Rejected(e), accounts),
+        },
+        StableResult::Ok(value) => (ExecutionResult::Returned(value), accounts),
+        StableResult::Err(e) => (ExecutionResult::Aborted(format!("{:?}", e)), accounts),
+    };
+
+    context.log_collector.program_consumed(
+        &program_id,
+        compute_units_available.saturating_sub(context.compute_units_remaining),
+        compute_units_available,
+    );
+    if !context.return_data.data.is_empty() {
+        context
+            .log_collector
+            .program_return(&program_id, &context.return_data.data);
+    }
+    if result.is_success() {
+        context.log_collector.program_success(&program_id);
+    } else {
+        context.log_collector.program_failure(&program_id, &result);
+    }
+
+    (instruction_count, result, output_accounts)
+}
+
+/// Runs a System Program instruction natively, as Solana runs its builtins.
+/// The accounts must already hold their latest state. Builtins log no compute
+/// consumption, but their changes are held to the same account rules as a program's.
+pub fn execute_system_program(
+    context: &mut SolanaContext,
+    accounts: Vec<Account>,
+    instruction_data: &[u8],
+) -> (ExecutionResult, Vec<Account>) {
+    let program_id = system_program::id();
+    context
+        .log_collector
+        .program_invoke(&program_id, context.invoke_stack.len() + 1);
+
+    let mut output_accounts = accounts.clone();
+    let result = match context.consume_checked(SYSTEM_PROGRAM_UNITS) {
+        Ok(()) => system_program::process_instruction(
+            &mut context.log_collector,
+            &mut output_accounts,
+            instruction_data,
+        )
+        .map_err(ExecutionResult::from)
+        .and_then(|()| {
+            account_rules::verify_account_changes(&program_id, &accounts, &output_accounts)
+                .map_err(ExecutionResult::Rejected)
+        }),
+        Err(e) => Err(ExecutionResult::Aborted(e.to_string())),
+    };
+
+    match result {
+        Ok(()) => {
+            context.update_accounts(&output_accounts);
+            context.log_collector.program_success(&program_id);
+            (ExecutionResult::Returned(0), output_accounts)
+        }
+        Err(result) => {
+            context.log_collector.program_failure(&program_id, &result);
+            (result, accounts)
+        }
+    }
+}
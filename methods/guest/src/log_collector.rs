@@ -0,0 +1,90 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use risc0_zkvm::guest::env;
+use runtime::Pubkey;
+
+/// Maximum bytes of log messages kept, as in Solana's log collector.
+pub const LOG_MESSAGES_BYTES_LIMIT: usize = 10 * 1000;
+
+/// Collects program output into a transaction-style log buffer.
+/// Messages use the same framing as Solana's stable log so indexers can parse them unchanged.
+#[derive(Default)]
+pub struct LogCollector {
+    pub messages: Vec<String>,
+    bytes_written: usize,
+    limit_warning: bool,
+}
+
+impl LogCollector {
+    /// Records a message, truncating once the byte limit is reached.
+    /// Messages are echoed to the host console as they are collected.
+    pub fn log(&mut self, message: &str) {
+        env::log(message);
+
+        let bytes_written = self.bytes_written.saturating_add(message.len());
+        if bytes_written >= LOG_MESSAGES_BYTES_LIMIT {
+            if !self.limit_warning {
+                self.limit_warning = true;
+                self.messages.push(String::from("Log truncated"));
+            }
+        } else {
+            self.bytes_written = bytes_written;
+            self.messages.push(message.to_string());
+        }
+    }
+
+    /// "Program <address> invoke [<depth>]"
+    pub fn program_invoke(&mut self, program_id: &Pubkey, invoke_depth: usize) {
+        self.log(&format!("Program {} invoke [{}]", program_id, invoke_depth));
+    }
+
+    /// "Program log: <program-generated output>"
+    pub fn program_log(&mut self, message: &str) {
+        self.log(&format!("Program log: {}", message));
+    }
+
+    /// "Program data: <binary-data-in-base64>*"
+    pub fn program_data(&mut self, data: &[&[u8]]) {
+        self.log(&format!(
+            "Program data: {}",
+            data.iter()
+                .map(|field| STANDARD.encode(field))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ));
+    }
+
+    /// "Program <address> consumed <units> of <limit> compute units"
+    pub fn program_consumed(&mut self, program_id: &Pubkey, consumed: u64, limit: u64) {
+        self.log(&format!(
+            "Program {} consumed {} of {} compute units",
+            program_id, consumed, limit
+        ));
+    }
+
+    /// "Program return: <program-id> <program-generated-data-in-base64>"
+    pub fn program_return(&mut self, program_id: &Pubkey, data: &[u8]) {
+        self.log(&format!(
+            "Program return: {} {}",
+            program_id,
+            STANDARD.encode(data)
+        ));
+    }
+
+    /// "Program <address> success"
+    pub fn program_success(&mut self, program_id: &Pubkey) {
+        self.log(&format!("Program {} success", program_id));
+    }
+
+    /// "Program <address> failed: <program error details>"
+    pub fn program_failure(&mut self, program_id: &Pubkey, err: &dyn std::fmt::Display) {
+        self.log(&format!("Program {} failed: {}", program_id, err));
+    }
+
+    /// The log buffer as a single newline-separated byte string, as hashed into the journal.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.messages.join("\n").into_bytes()
+    }
+}
@@ -1,26 +1,59 @@
 // Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
 // SPDX-License-Identifier: MIT
 
-use crate::{runtime::Pubkey, serializer::Serializer};
-use risc0_zkvm::guest::env;
+use crate::{allocator::BpfAllocator, invoke::InvokeFrame, log_collector::LogCollector};
+use risc0_zkvm::{
+    guest::env,
+    sha::{Impl, Sha256},
+};
+use runtime::{
+    input::{Input, Program},
+    journal::{encode_account_state, Hash, Journal, ProgramHash, ReturnData, JOURNAL_VERSION},
+    sysvar::SysvarCache,
+    Account, Pubkey,
+};
 use solana_sbpf::{
-    aligned_memory::AlignedMemory,
     elf::Executable,
-    memory_region::{MemoryMapping, MemoryRegion},
-    program::BuiltinProgram,
-    vm::{Config, EbpfVm},
+    program::{BuiltinProgram, SBPFVersion},
+    vm::Config,
 };
 use std::sync::Arc;
-mod runtime;
+mod account_rules;
+mod allocator;
+mod compute_budget;
+mod invoke;
+mod log_collector;
 mod serializer;
 mod syscalls;
+mod system_program;
+mod transaction;
 
-/// Solana execution context that tracks compute unit consumption.
-/// Mirrors Solana's on-chain compute budget mechanism.
+/// Solana execution context that tracks compute unit consumption, program logs
+/// and return data. Mirrors Solana's on-chain compute budget mechanism.
 #[derive(Default)]
 pub struct SolanaContext {
     pub compute_units_remaining: u64,
     pub compute_units_consumed: u64,
+    pub log_collector: LogCollector,
+    /// Program currently executing, as seen by syscalls.
+    pub program_id: Pubkey,
+    pub return_data: ReturnData,
+    /// Sysvars declared by the host.
+    pub sysvars: SysvarCache,
+    /// Bump allocator behind sol_alloc_free_.
+    pub allocator: BpfAllocator,
+    /// Heap frame given to every program on the invoke stack.
+    pub heap_size: u32,
+    /// Programs currently executing, innermost last.
+    pub invoke_stack: Vec<InvokeFrame>,
+    /// Latest state of every account in the transaction, updated as programs return.
+    pub transaction_accounts: Vec<Account>,
+    /// Programs that can be invoked through CPI.
+    pub programs: Vec<Program>,
+    /// Loader holding the registered syscalls, used to load CPI targets.
+    pub loader: Option<Arc<BuiltinProgram<SolanaContext>>>,
+    /// Programs already loaded, keyed by program id, so each ELF is verified once.
+    pub executables: Vec<(Pubkey, Arc<Executable<SolanaContext>>)>,
 }
 
 impl SolanaContext {
@@ -34,6 +67,19 @@ impl SolanaContext {
 }
 
 impl SolanaContext {
+    /// Deducts compute units, failing like Solana's compute meter once the budget is exhausted.
+    pub fn consume_checked(
+        &mut self,
+        units: u64,
+    ) -> Result<(), Box<dyn core::error::Error + Send + Sync>> {
+        let exceeded = self.compute_units_remaining < units;
+        self.consume_compute_units(units);
+        if exceeded {
+            return Err("Computational budget exceeded".into());
+        }
+        Ok(())
+    }
+
     /// Alias for consume_compute_units for compatibility.
     pub fn consume_gas(&mut self, units: u64) {
         self.consume_compute_units(units);
@@ -55,15 +101,53 @@ impl solana_sbpf::vm::ContextObject for SolanaContext {
     }
 }
 
+/// Hashes bytes with the zkVM's accelerated SHA-256.
+fn sha256(bytes: &[u8]) -> Hash {
+    Impl::hash_bytes(bytes)
+        .as_bytes()
+        .try_into()
+        .expect("SHA-256 digest is 32 bytes")
+}
+
+/// Maps an SBPF version number from the execution parameters to the loader's version.
+fn sbpf_version(version: u8) -> SBPFVersion {
+    match version {
+        0 => SBPFVersion::V0,
+        1 => SBPFVersion::V1,
+        2 => SBPFVersion::V2,
+        3 => SBPFVersion::V3,
+        _ => unreachable!("SBPF version {} rejected by validate", version),
+    }
+}
+
 /// Guest program that executes Solana BPF bytecode inside the zkVM.
 /// Sets up the SBPF virtual machine with proper memory regions and syscalls,
 /// then executes the provided bytecode and commits the result.
 fn main() {
-    // Read Solana BPF bytecode from host
+    // Read Solana BPF bytecode and the input envelope from host
     let bytecode: Vec<u8> = env::read();
+    let Input {
+        program_id,
+        mut accounts,
+        instruction_data,
+        parameters: execution_parameters,
+        sysvars,
+        programs,
+        message,
+        commit_logs,
+    } = env::read();
+
+    // Validate the requested heap frame and VM settings before building the loader
+    if let Err(e) = execution_parameters.validate() {
+        panic!("Invalid execution parameters: {}", e);
+    }
 
     // Configure SBPF loader with Solana-compatible settings
     let mut loader = BuiltinProgram::<SolanaContext>::new_loader(Config {
+        max_call_depth: execution_parameters.max_call_depth as usize,
+        stack_frame_size: execution_parameters.stack_frame_size as usize,
+        enabled_sbpf_versions: sbpf_version(execution_parameters.min_sbpf_version)
+            ..=sbpf_version(execution_parameters.max_sbpf_version),
         enable_symbol_and_section_labels: true,
         reject_broken_elfs: true,
         enable_instruction_tracing: true,
@@ -80,75 +164,104 @@ fn main() {
                 "Detected SBPF Version: {:?}",
                 exec.get_sbpf_version()
             ));
-            exec
+            Arc::new(exec)
         }
         Err(e) => {
             panic!("Failed to create executable: {:?}", e);
         }
     };
-    let sbpf_version = executable.get_sbpf_version();
-    let config = executable.get_config();
-    let stack_size = config.stack_size();
-
-    // Allocate stack memory for VM execution
-    let mut stack = AlignedMemory::<{ solana_sbpf::ebpf::HOST_ALIGN }>::zero_filled(stack_size);
-    let stack_len = stack.len();
-
-    // Allocate heap memory (32KB default)
-    let heap_size = 32 * 1024;
-    let mut heap = AlignedMemory::<{ solana_sbpf::ebpf::HOST_ALIGN }>::zero_filled(heap_size);
-
-    // Set up program ID (placeholder for now)
-    let program_id = Pubkey::try_from("zkRXxvKMqQYgPRAkBHwYKCvnF8YjVtXW1BK4VCXpkeo".to_string())
-        .expect("valid bs58");
-
-    // Serialize input parameters in Solana's format
-    let (_, parameter_regions, _) = Serializer::serialize_parameters(vec![], &[], program_id);
-
-    // Build memory regions for the VM (code, stack, heap, input)
-    let regions: Vec<MemoryRegion> = vec![
-        executable.get_ro_region(),
-        MemoryRegion::new_writable_gapped(
-            stack.as_slice_mut(),
-            solana_sbpf::ebpf::MM_STACK_START,
-            if !sbpf_version.dynamic_stack_frames() && config.enable_stack_frame_gaps {
-                config.stack_frame_size as u64
-            } else {
-                0
-            },
-        ),
-        MemoryRegion::new_writable(heap.as_slice_mut(), solana_sbpf::ebpf::MM_HEAP_START),
-    ]
-    .into_iter()
-    .chain(parameter_regions)
-    .collect();
-
-    // Create memory mapping for VM
-    let memory_mapping = match MemoryMapping::new(regions, config, sbpf_version) {
-        Ok(m) => m,
-        Err(e) => panic!("Failed to create memory regions: {:?}", e),
-    };
 
-    // Initialize execution context with Solana's default compute budget
+    let program_hash = sha256(&bytecode);
+    // Without an explicit program id the program is addressed by its ELF hash, so the
+    // same bytecode always runs as the same program
+    let program_id = program_id.unwrap_or_else(|| Pubkey::from(program_hash));
+    let instruction_data_hash = sha256(&instruction_data);
+    let program_hashes = programs
+        .iter()
+        .map(|program| ProgramHash {
+            program_id: program.program_id,
+            program_hash: sha256(&program.elf),
+        })
+        .collect();
+
+    // A transaction's accounts are its account keys, with the privileges its header grants
+    let message_hash = message.as_ref().map(|message| {
+        if let Err(e) = message.sanitize() {
+            panic!("Invalid transaction: {}", e);
+        }
+        if accounts.len() != message.account_keys.len()
+            || accounts
+                .iter()
+                .zip(&message.account_keys)
+                .any(|(account, key)| account.pubkey != *key)
+        {
+            panic!("Invalid transaction: accounts do not match the message's account keys");
+        }
+        for (index, account) in accounts.iter_mut().enumerate() {
+            account.is_signer = message.is_signer(index);
+            account.is_writable = message.is_writable(index);
+        }
+        sha256(&message.serialize())
+    });
+
+    if let Err(e) = account_rules::verify_duplicate_accounts(&accounts) {
+        panic!("Invalid input accounts: {}", e);
+    }
+    let input_accounts_hash = sha256(&encode_account_state(&accounts));
+
+    // Initialize execution context with the requested compute budget
     let mut context = SolanaContext {
-        compute_units_remaining: 200_000,
+        compute_units_remaining: execution_parameters.compute_unit_limit,
         compute_units_consumed: 0,
+        program_id,
+        sysvars: sysvars.clone(),
+        heap_size: execution_parameters.heap_size,
+        transaction_accounts: accounts.clone(),
+        programs,
+        loader: Some(executable.get_loader().clone()),
+        // The invoked program can call itself without being loaded again
+        executables: vec![(program_id, executable.clone())],
+        ..SolanaContext::default()
     };
 
-    // Create and configure the SBPF virtual machine
-    let mut vm = EbpfVm::new(
-        executable.get_loader().clone(),
-        sbpf_version,
-        &mut context,
-        memory_mapping,
-        stack_len,
-    );
-
-    // Execute the Solana program and capture results
-    let (instruction_count, result) = vm.execute_program(&executable, true);
+    // Execute the transaction or the single instruction and capture results
+    let (instruction_count, result, output_accounts) = match &message {
+        Some(message) => {
+            transaction::execute_transaction(&mut context, &executable, program_id, message)
+        }
+        None => invoke::execute_program(
+            &mut context,
+            &executable,
+            program_id,
+            accounts,
+            &instruction_data,
+        ),
+    };
     env::log(&format!("Instruction Count: {}", instruction_count));
     env::log(&format!("Result: {:?}", result));
+    let output_accounts_hash = sha256(&encode_account_state(&output_accounts));
+
+    let return_data = Some(context.return_data).filter(|r| !r.data.is_empty());
+    let log_digest = sha256(&context.log_collector.to_bytes());
 
-    // Commit execution success/failure to the proof journal
-    env::commit(&result.is_ok());
+    // Commit the execution summary to the proof journal
+    env::commit(&Journal {
+        version: JOURNAL_VERSION,
+        program_hash,
+        program_id,
+        instruction_data_hash,
+        message_hash,
+        parameters: execution_parameters,
+        sysvars,
+        programs: program_hashes,
+        input_accounts_hash,
+        output_accounts_hash,
+        output_accounts,
+        result,
+        compute_units_consumed: context.compute_units_consumed,
+        instruction_count,
+        log_digest,
+        logs: commit_logs.then_some(context.log_collector.messages),
+        return_data,
+    });
 }
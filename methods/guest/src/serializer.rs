@@ -8,7 +8,7 @@ use solana_sbpf::{
     memory_region::MemoryRegion,
 };
 
-use crate::runtime::{Account, Pubkey};
+use runtime::{Account, Pubkey};
 
 /// Serializer for converting Solana account data into SBPF VM memory format.
 /// Handles memory layout, alignment, and region management for VM input.
@@ -23,18 +23,26 @@ pub struct Serializer {
 pub const BPF_ALIGN_OF_U128: usize = 8;
 pub const NON_DUP_MARKER: u8 = u8::MAX;
 pub const MAX_PERMITTED_DATA_INCREASE: usize = 1_024 * 10; // 10KB max growth
+pub const MAX_PERMITTED_DATA_LENGTH: usize = 10 * 1_024 * 1_024; // 10MB max account size
 pub type Address = u64;
 
 /// Represents a serialized account in VM memory with address pointers.
-#[allow(dead_code)]
 pub struct VmSerializedAccount {
-    public_key_addr: Address,
-    owner_key_addr: Address,
-    lamports_addr: Address,
-    data_addr: Address,
+    #[allow(dead_code)]
+    pub public_key_addr: Address,
+    pub owner_key_addr: Address,
+    pub lamports_addr: Address,
+    pub data_addr: Address,
     pub original_data_len: usize,
 }
 
+/// Slot in the input region: either a fully serialized account or a reference to the
+/// earlier slot holding the same key, as Solana's loader writes duplicate accounts.
+pub enum SerializedAccount {
+    Account(VmSerializedAccount),
+    Duplicate(usize),
+}
+
 impl Serializer {
     /// Creates a new serializer with specified buffer size and starting virtual address.
     pub fn new(size: usize, start_addr: Address) -> Self {
@@ -110,21 +118,43 @@ impl Serializer {
 
     /// Serializes accounts and instruction data in Solana's input format.
     /// Returns memory buffer, memory regions for VM mapping, and account metadata.
+    /// Fails if a duplicate refers to a position its one-byte marker cannot hold.
     pub fn serialize_parameters(
         accounts: Vec<Account>,
         instruction_data: &[u8],
         program_id: Pubkey,
-    ) -> (
-        AlignedMemory<HOST_ALIGN>,
-        Vec<MemoryRegion>,
-        Vec<VmSerializedAccount>,
-    ) {
+    ) -> Result<
+        (
+            AlignedMemory<HOST_ALIGN>,
+            Vec<MemoryRegion>,
+            Vec<SerializedAccount>,
+        ),
+        String,
+    > {
         env::log(&format!("number of accounts: {}", accounts.len()));
 
+        let duplicates = duplicate_positions(&accounts);
+        // The position shares a byte with the marker, so it must stay below it
+        if let Some(position) = duplicates
+            .iter()
+            .flatten()
+            .find(|position| **position >= NON_DUP_MARKER as usize)
+        {
+            return Err(format!(
+                "Duplicate of account at position {} cannot be serialized",
+                position
+            ));
+        }
+
         // Calculate total buffer size needed for serialization
 
         let mut size = size_of::<u64>();
-        for account in &accounts {
+        for (account, duplicate) in accounts.iter().zip(&duplicates) {
+            if duplicate.is_some() {
+                size += 1 // position
+                + 7; // padding
+                continue;
+            }
             let data_len = account.data.len();
             size += 1 // dup
             + size_of::<u8>() // is_signer
@@ -135,10 +165,11 @@ impl Serializer {
             + size_of::<Pubkey>() // owner
             + size_of::<u64>()  // lamports
             + size_of::<u64>()  // data len
-            + size_of::<u64>() // rent epoch
-            + data_len
-                + MAX_PERMITTED_DATA_INCREASE
-                + (size as *const u8).align_offset(BPF_ALIGN_OF_U128);
+            + data_len;
+            // The growth padding aligns the end of the data, as in write_account
+            size += MAX_PERMITTED_DATA_INCREASE
+                + (size as *const u8).align_offset(BPF_ALIGN_OF_U128)
+                + size_of::<u64>(); // rent epoch
         }
 
         size += size_of::<u64>(); // data len
@@ -150,7 +181,15 @@ impl Serializer {
 
         // Serialize accounts in Solana's expected format
         s.write((accounts.len() as u64).to_le());
-        for mut account in accounts {
+        for (mut account, duplicate) in accounts.into_iter().zip(duplicates) {
+            if let Some(position) = duplicate {
+                // Duplicates only record the position of the first occurrence
+                s.write::<u8>(position as u8); // below NON_DUP_MARKER, checked above
+                s.write_all(&[0u8, 0, 0, 0, 0, 0, 0]);
+                serialized_accounts.push(SerializedAccount::Duplicate(position));
+                continue;
+            }
+
             s.write::<u8>(NON_DUP_MARKER);
             s.write::<u8>(account.is_signer as u8);
             s.write::<u8>(account.is_writable as u8);
@@ -164,13 +203,13 @@ impl Serializer {
             // Rent epoch
             s.write::<u64>(account.rent_epoch.to_le());
 
-            serialized_accounts.push(VmSerializedAccount {
+            serialized_accounts.push(SerializedAccount::Account(VmSerializedAccount {
                 public_key_addr,
                 owner_key_addr,
                 lamports_addr,
                 data_addr,
                 original_data_len: account.data.len(),
-            });
+            }));
         }
 
         s.write::<u64>((instruction_data.len() as u64).to_le());
@@ -178,7 +217,70 @@ impl Serializer {
         s.write_all(program_id.as_ref());
         let (memory, regions) = s.finish();
 
-        (memory, regions, serialized_accounts)
+        Ok((memory, regions, serialized_accounts))
+    }
+
+    /// Reads the account state left by the program back out of the input region.
+    /// Only lamports, data and owner can be changed by the program; everything else
+    /// is carried over from the accounts that were serialized. Duplicate slots take
+    /// the state of the slot they reference, keeping their own signer/writable flags.
+    pub fn deserialize_parameters(
+        memory: &[u8],
+        accounts: &[Account],
+        serialized_accounts: &[SerializedAccount],
+    ) -> Result<Vec<Account>, String> {
+        let read = |addr: Address, len: usize| -> Result<&[u8], String> {
+            let start = addr.saturating_sub(MM_INPUT_START) as usize;
+            memory
+                .get(start..start.saturating_add(len))
+                .ok_or_else(|| format!("Address 0x{:x} outside of input region", addr))
+        };
+        let read_u64 = |addr: Address| -> Result<u64, String> {
+            let bytes = read(addr, size_of::<u64>())?;
+            Ok(u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+        };
+
+        let mut post_accounts: Vec<Account> = Vec::with_capacity(accounts.len());
+        for (account, serialized) in accounts.iter().zip(serialized_accounts) {
+            let serialized = match serialized {
+                SerializedAccount::Account(serialized) => serialized,
+                SerializedAccount::Duplicate(position) => {
+                    let original = &post_accounts[*position];
+                    post_accounts.push(Account {
+                        lamports: original.lamports,
+                        data: original.data.clone(),
+                        owner: original.owner,
+                        ..account.clone()
+                    });
+                    continue;
+                }
+            };
+
+            let owner = Pubkey::try_from(read(serialized.owner_key_addr, size_of::<Pubkey>())?)?;
+            let lamports = read_u64(serialized.lamports_addr)?;
+            // The data length immediately follows the lamports
+            let post_len = read_u64(serialized.lamports_addr + size_of::<u64>() as u64)? as usize;
+
+            // Data may only grow into the padding reserved after the original data
+            if post_len.saturating_sub(serialized.original_data_len) > MAX_PERMITTED_DATA_INCREASE
+                || post_len > MAX_PERMITTED_DATA_LENGTH
+            {
+                return Err(format!(
+                    "Invalid realloc of account {}: {} -> {} bytes",
+                    account.pubkey, serialized.original_data_len, post_len
+                ));
+            }
+            let data = read(serialized.data_addr, post_len)?.to_vec();
+
+            post_accounts.push(Account {
+                lamports,
+                data,
+                owner,
+                ..account.clone()
+            });
+        }
+
+        Ok(post_accounts)
     }
 
     fn debug_assert_alignment<T>(&self) {
@@ -192,3 +294,128 @@ impl Serializer {
         );
     }
 }
+
+/// Finds, for each account, the position of an earlier account with the same key.
+fn duplicate_positions(accounts: &[Account]) -> Vec<Option<usize>> {
+    accounts
+        .iter()
+        .enumerate()
+        .map(|(index, account)| {
+            accounts[..index]
+                .iter()
+                .position(|earlier| earlier.pubkey == account.pubkey)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(seed: u8, data: Vec<u8>) -> Account {
+        Account {
+            pubkey: Pubkey::from([seed; 32]),
+            is_signer: false,
+            is_writable: true,
+            lamports: 100,
+            data,
+            owner: Pubkey::from([9; 32]),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn serialized(serialized_account: &SerializedAccount) -> &VmSerializedAccount {
+        match serialized_account {
+            SerializedAccount::Account(serialized) => serialized,
+            SerializedAccount::Duplicate(_) => panic!("expected a serialized account"),
+        }
+    }
+
+    /// Offset in the input region of the slot following a serialized account.
+    fn next_slot(serialized: &VmSerializedAccount) -> usize {
+        let data_end = (serialized.data_addr - MM_INPUT_START) as usize
+            + serialized.original_data_len
+            + MAX_PERMITTED_DATA_INCREASE;
+        data_end.next_multiple_of(BPF_ALIGN_OF_U128) + size_of::<u64>() // rent epoch
+    }
+
+    #[test]
+    fn serialize_duplicate_accounts() {
+        let first = account(1, vec![]);
+        let second = account(2, vec![1, 2, 3]);
+        let duplicate = Account {
+            is_signer: true,
+            ..second.clone()
+        };
+        let (memory, _, serialized_accounts) = Serializer::serialize_parameters(
+            vec![first, second, duplicate],
+            &[7],
+            Pubkey::default(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            serialized_accounts[2],
+            SerializedAccount::Duplicate(1)
+        ));
+        // The duplicate is its position followed by 7 bytes of padding
+        let offset = next_slot(serialized(&serialized_accounts[1]));
+        let memory = memory.as_slice();
+        assert_eq!(memory[offset..offset + 8], [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(memory[offset + 8..offset + 17], [1, 0, 0, 0, 0, 0, 0, 0, 7]);
+        assert_eq!(memory.len(), offset + 17 + size_of::<Pubkey>());
+    }
+
+    #[test]
+    fn reject_duplicate_beyond_marker() {
+        let mut accounts: Vec<Account> = (0..=u8::MAX).map(|seed| account(seed, vec![])).collect();
+        accounts.push(accounts[NON_DUP_MARKER as usize].clone());
+        assert!(Serializer::serialize_parameters(accounts, &[], Pubkey::default()).is_err());
+    }
+
+    #[test]
+    fn deserialize_duplicate_accounts() {
+        let original = account(1, vec![1, 2, 3]);
+        let duplicate = Account {
+            is_writable: false,
+            ..original.clone()
+        };
+        let accounts = vec![original, duplicate];
+        let (mut memory, _, serialized_accounts) =
+            Serializer::serialize_parameters(accounts.clone(), &[], Pubkey::default()).unwrap();
+
+        let serialized_account = serialized(&serialized_accounts[0]);
+        let lamports = (serialized_account.lamports_addr - MM_INPUT_START) as usize;
+        let data = (serialized_account.data_addr - MM_INPUT_START) as usize;
+        let memory = memory.as_slice_mut();
+        memory[lamports..lamports + 8].copy_from_slice(&42u64.to_le_bytes());
+        memory[data..data + 3].copy_from_slice(&[4, 5, 6]);
+
+        let post_accounts =
+            Serializer::deserialize_parameters(memory, &accounts, &serialized_accounts).unwrap();
+        for (pre, post) in accounts.iter().zip(&post_accounts) {
+            assert_eq!(post.lamports, 42);
+            assert_eq!(post.data, [4, 5, 6]);
+            assert_eq!(post.is_writable, pre.is_writable);
+        }
+    }
+
+    #[test]
+    fn data_growth_limit() {
+        let accounts = vec![account(1, vec![1, 2, 3])];
+        let grow = |increase: usize| {
+            let (mut memory, _, serialized_accounts) =
+                Serializer::serialize_parameters(accounts.clone(), &[], Pubkey::default()).unwrap();
+            let serialized_account = serialized(&serialized_accounts[0]);
+            let data_len = (serialized_account.lamports_addr - MM_INPUT_START) as usize + 8;
+            let new_len = (3 + increase) as u64;
+            memory.as_slice_mut()[data_len..data_len + 8].copy_from_slice(&new_len.to_le_bytes());
+            Serializer::deserialize_parameters(memory.as_slice(), &accounts, &serialized_accounts)
+        };
+
+        let post_accounts = grow(MAX_PERMITTED_DATA_INCREASE).unwrap();
+        assert_eq!(post_accounts[0].data.len(), 3 + MAX_PERMITTED_DATA_INCREASE);
+        assert!(grow(MAX_PERMITTED_DATA_INCREASE + 1).is_err());
+    }
+}
@@ -1,16 +1,103 @@
 // Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
 // SPDX-License-Identifier: MIT
 
-use crate::SolanaContext;
+use crate::{
+    compute_budget::{CPI_BYTES_PER_UNIT, LOG_64_UNITS, LOG_PUBKEY_UNITS, SYSCALL_BASE_COST},
+    serializer::BPF_ALIGN_OF_U128,
+    SolanaContext,
+};
 use risc0_zkvm::guest::env;
+use runtime::{journal::ReturnData, Pubkey};
 use solana_sbpf::{
-    declare_builtin_function, error::StableResult, memory_region::MemoryMapping,
+    declare_builtin_function,
+    error::StableResult,
+    memory_region::{AccessType, MemoryMapping},
     program::BuiltinProgram,
 };
 use std::slice;
 
+/// Maximum size of return data, as on Solana.
+pub const MAX_RETURN_DATA: usize = 1024;
+
+type Error = Box<dyn core::error::Error + Send + Sync>;
+
+mod alt_bn128;
+mod big_mod_exp;
+mod cpi;
+mod curve25519;
+mod hash;
+mod pda;
+mod poseidon;
+mod secp256k1;
+mod sysvar;
+
+/// Maps a VM address range to a host slice for reading.
+fn translate_slice<'a>(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+) -> Result<&'a [u8], Error> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    match memory_mapping.map(AccessType::Load, addr, len) {
+        StableResult::Ok(ptr) => {
+            Ok(unsafe { slice::from_raw_parts(ptr as *const u8, len as usize) })
+        }
+        StableResult::Err(e) => Err(format!("Memory mapping failed: {:?}", e).into()),
+    }
+}
+
+/// Maps a VM address range to a host slice for writing.
+fn translate_slice_mut<'a>(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+) -> Result<&'a mut [u8], Error> {
+    if len == 0 {
+        return Ok(&mut []);
+    }
+    match memory_mapping.map(AccessType::Store, addr, len) {
+        StableResult::Ok(ptr) => {
+            Ok(unsafe { slice::from_raw_parts_mut(ptr as *mut u8, len as usize) })
+        }
+        StableResult::Err(e) => Err(format!("Memory mapping failed: {:?}", e).into()),
+    }
+}
+
+/// Maps a VM array of `(addr, len)` slice descriptors, as passed by `&[&[u8]]`
+/// arguments, to host slices.
+fn translate_slices<'a>(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+) -> Result<Vec<&'a [u8]>, Error> {
+    let descriptors = translate_slice(
+        memory_mapping,
+        addr,
+        len.saturating_mul(2 * size_of::<u64>() as u64),
+    )?;
+    let read_u64 = |offset: usize| {
+        u64::from_le_bytes(
+            descriptors[offset..offset + size_of::<u64>()]
+                .try_into()
+                .expect("8 bytes"),
+        )
+    };
+    (0..len as usize)
+        .map(|index| {
+            let offset = index * 2 * size_of::<u64>();
+            translate_slice(
+                memory_mapping,
+                read_u64(offset),
+                read_u64(offset + size_of::<u64>()),
+            )
+        })
+        .collect()
+}
+
 // Implements Solana's sol_log_ syscall for printing messages.
-// Maps guest memory to host memory and outputs the message to the zkVM log.
+// Maps guest memory to host memory and records the message in the program logs.
 declare_builtin_function!(
     SyscallLog,
     fn rust(
@@ -22,22 +109,111 @@ declare_builtin_function!(
         _arg5: u64,
         memory_mapping: &mut MemoryMapping,
     ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
-        context.consume_gas(1);
+        context.consume_checked(SYSCALL_BASE_COST.max(len))?;
 
-        // Map the memory region and get the host address
-        let host_addr = memory_mapping
-            .map(solana_sbpf::memory_region::AccessType::Load, addr, len)
-            .map_err(|e| format!("Memory mapping failed: {:?}", e))
-            .unwrap();
-
-        // Create a slice from the mapped memory
-        let msg_slice = unsafe { slice::from_raw_parts(host_addr as *const u8, len as usize) };
+        // Map the message out of VM memory; a bad pointer aborts the program
+        let msg_slice = translate_slice(memory_mapping, addr, len)?;
 
         // Convert bytes to UTF-8 string
         let message = str::from_utf8(msg_slice).map_err(|_| "Invalid UTF-8 in log message")?;
 
-        env::log(message);
+        context.log_collector.program_log(message);
+
+        Ok(0)
+    }
+);
+
+// Implements sol_log_64_ for logging five values as hex.
+declare_builtin_function!(
+    SyscallLogU64,
+    fn rust(
+        context: &mut SolanaContext,
+        arg1: u64,
+        arg2: u64,
+        arg3: u64,
+        arg4: u64,
+        arg5: u64,
+        _memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(LOG_64_UNITS)?;
+
+        context.log_collector.program_log(&format!(
+            "{:#x}, {:#x}, {:#x}, {:#x}, {:#x}",
+            arg1, arg2, arg3, arg4, arg5
+        ));
+        Ok(0)
+    }
+);
+
+// Implements sol_log_compute_units_, logging the units left after its own cost.
+declare_builtin_function!(
+    SyscallLogComputeUnits,
+    fn rust(
+        context: &mut SolanaContext,
+        _arg1: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        _memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(SYSCALL_BASE_COST)?;
+
+        let remaining = context.compute_units_remaining;
+        context.log_collector.log(&format!(
+            "Program consumption: {} units remaining",
+            remaining
+        ));
+        Ok(0)
+    }
+);
+
+// Implements sol_log_pubkey, logging the key as base58.
+declare_builtin_function!(
+    SyscallLogPubkey,
+    fn rust(
+        context: &mut SolanaContext,
+        pubkey_addr: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(LOG_PUBKEY_UNITS)?;
 
+        let pubkey = Pubkey::try_from(translate_slice(
+            memory_mapping,
+            pubkey_addr,
+            size_of::<Pubkey>() as u64,
+        )?)?;
+        context.log_collector.program_log(&pubkey.to_string());
+        Ok(0)
+    }
+);
+
+// Implements sol_log_data, used by Anchor events. Charges the base cost per field
+// plus a unit per byte and logs the fields as space-separated base64.
+declare_builtin_function!(
+    SyscallLogData,
+    fn rust(
+        context: &mut SolanaContext,
+        addr: u64,
+        len: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(SYSCALL_BASE_COST)?;
+        context.consume_checked(SYSCALL_BASE_COST.saturating_mul(len))?;
+
+        let fields = translate_slices(memory_mapping, addr, len)?;
+        context.consume_checked(fields.iter().fold(0u64, |total, field| {
+            total.saturating_add(field.len() as u64)
+        }))?;
+
+        context.log_collector.program_data(&fields);
         Ok(0)
     }
 );
@@ -223,17 +399,174 @@ declare_builtin_function!(
     }
 );
 
+// Implements the deprecated sol_alloc_free_ syscall with Solana's bump semantics.
+// Returns the address of the allocation, or 0 if the heap is exhausted.
+// Freeing is a no-op that also returns 0.
+declare_builtin_function!(
+    SyscallAllocFree,
+    fn rust(
+        context: &mut SolanaContext,
+        size: u64,
+        free_addr: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        _memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        if free_addr != 0 {
+            return Ok(0);
+        }
+        Ok(context
+            .allocator
+            .alloc(size, BPF_ALIGN_OF_U128 as u64)
+            .unwrap_or(0))
+    }
+);
+
+// Implements sol_set_return_data for passing data back to the caller.
+// Records the data together with the id of the program that set it.
+declare_builtin_function!(
+    SyscallSetReturnData,
+    fn rust(
+        context: &mut SolanaContext,
+        addr: u64,
+        len: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked((len / CPI_BYTES_PER_UNIT).saturating_add(SYSCALL_BASE_COST))?;
+
+        if len > MAX_RETURN_DATA as u64 {
+            return Err(format!("Return data too large ({} > {})", len, MAX_RETURN_DATA).into());
+        }
+
+        let data = if len == 0 {
+            Vec::new()
+        } else {
+            translate_slice(memory_mapping, addr, len)?.to_vec()
+        };
+        context.return_data = ReturnData {
+            program_id: context.program_id,
+            data,
+        };
+
+        Ok(0)
+    }
+);
+
+// Implements sol_get_return_data for reading data set by the last program.
+// Copies up to `length` bytes plus the setting program id and returns the full length.
+declare_builtin_function!(
+    SyscallGetReturnData,
+    fn rust(
+        context: &mut SolanaContext,
+        return_data_addr: u64,
+        length: u64,
+        program_id_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(SYSCALL_BASE_COST)?;
+
+        let return_data_len = context.return_data.data.len() as u64;
+        let length = length.min(return_data_len);
+        if length != 0 {
+            context.consume_checked(
+                length.saturating_add(size_of::<Pubkey>() as u64) / CPI_BYTES_PER_UNIT,
+            )?;
+
+            let data = translate_slice_mut(memory_mapping, return_data_addr, length)?;
+            let program_id =
+                translate_slice_mut(memory_mapping, program_id_addr, size_of::<Pubkey>() as u64)?;
+            if is_overlapping(data, program_id) {
+                return Err("Overlapping copy".into());
+            }
+
+            data.copy_from_slice(&context.return_data.data[..length as usize]);
+            program_id.copy_from_slice(context.return_data.program_id.as_ref());
+        }
+
+        // Return the actual length, rather than the length copied
+        Ok(return_data_len)
+    }
+);
+
+/// Whether two host slices share any bytes.
+fn is_overlapping(a: &[u8], b: &[u8]) -> bool {
+    let a = a.as_ptr_range();
+    let b = b.as_ptr_range();
+    a.start < b.end && b.start < a.end
+}
+
 /// Registers all implemented Solana syscalls with the SBPF loader.
 /// These syscalls provide the runtime interface for Solana programs.
 pub fn register_syscalls(
     loader: &mut BuiltinProgram<SolanaContext>,
 ) -> Result<(), Box<dyn core::error::Error>> {
     loader.register_function("sol_log_", SyscallLog::vm)?;
+    loader.register_function("sol_log_64_", SyscallLogU64::vm)?;
+    loader.register_function("sol_log_compute_units_", SyscallLogComputeUnits::vm)?;
+    loader.register_function("sol_log_pubkey", SyscallLogPubkey::vm)?;
+    loader.register_function("sol_log_data", SyscallLogData::vm)?;
     loader.register_function("abort", SyscallAbort::vm)?;
     loader.register_function("sol_panic_", SyscallAbort::vm)?;
     loader.register_function("sol_memcpy_", SyscallMemcpy::vm)?;
     loader.register_function("sol_memmove_", SyscallMemmove::vm)?;
     loader.register_function("sol_memset_", SyscallMemset::vm)?;
     loader.register_function("sol_memcmp_", SyscallMemcmp::vm)?;
+    loader.register_function("sol_alloc_free_", SyscallAllocFree::vm)?;
+    loader.register_function("sol_set_return_data", SyscallSetReturnData::vm)?;
+    loader.register_function("sol_get_return_data", SyscallGetReturnData::vm)?;
+    loader.register_function("sol_sha256", hash::SyscallSha256::vm)?;
+    loader.register_function("sol_keccak256", hash::SyscallKeccak256::vm)?;
+    loader.register_function("sol_blake3", hash::SyscallBlake3::vm)?;
+    loader.register_function(
+        "sol_secp256k1_recover",
+        secp256k1::SyscallSecp256k1Recover::vm,
+    )?;
+    loader.register_function("sol_alt_bn128_group_op", alt_bn128::SyscallAltBn128::vm)?;
+    loader.register_function(
+        "sol_alt_bn128_compression",
+        alt_bn128::SyscallAltBn128Compression::vm,
+    )?;
+    loader.register_function(
+        "sol_curve_validate_point",
+        curve25519::SyscallCurvePointValidation::vm,
+    )?;
+    loader.register_function("sol_curve_group_op", curve25519::SyscallCurveGroupOps::vm)?;
+    loader.register_function(
+        "sol_curve_multiscalar_mul",
+        curve25519::SyscallCurveMultiscalarMultiplication::vm,
+    )?;
+    loader.register_function("sol_poseidon", poseidon::SyscallPoseidon::vm)?;
+    loader.register_function(
+        "sol_create_program_address",
+        pda::SyscallCreateProgramAddress::vm,
+    )?;
+    loader.register_function(
+        "sol_try_find_program_address",
+        pda::SyscallTryFindProgramAddress::vm,
+    )?;
+    loader.register_function("sol_get_clock_sysvar", sysvar::SyscallGetClockSysvar::vm)?;
+    loader.register_function("sol_get_rent_sysvar", sysvar::SyscallGetRentSysvar::vm)?;
+    loader.register_function(
+        "sol_get_epoch_schedule_sysvar",
+        sysvar::SyscallGetEpochScheduleSysvar::vm,
+    )?;
+    loader.register_function(
+        "sol_get_epoch_rewards_sysvar",
+        sysvar::SyscallGetEpochRewardsSysvar::vm,
+    )?;
+    loader.register_function(
+        "sol_get_last_restart_slot",
+        sysvar::SyscallGetLastRestartSlotSysvar::vm,
+    )?;
+    loader.register_function("sol_get_sysvar", sysvar::SyscallGetSysvar::vm)?;
+    loader.register_function("sol_big_mod_exp", big_mod_exp::SyscallBigModExp::vm)?;
+    loader.register_function("sol_invoke_signed_rust", cpi::SyscallInvokeSignedRust::vm)?;
+    loader.register_function("sol_invoke_signed_c", cpi::SyscallInvokeSignedC::vm)?;
     Ok(())
 }
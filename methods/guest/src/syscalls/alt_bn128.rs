@@ -0,0 +1,668 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! alt_bn128 (BN254) syscalls used by on-chain Groth16 verifiers.
+//! Points use the EIP-197 big-endian encoding and failures are reported with
+//! Solana's error codes, so programs see exactly what they would on chain.
+
+use super::{translate_slice, translate_slice_mut};
+use crate::{
+    compute_budget::{
+        ALT_BN128_ADDITION_COST, ALT_BN128_G1_COMPRESS_COST, ALT_BN128_G1_DECOMPRESS_COST,
+        ALT_BN128_G2_COMPRESS_COST, ALT_BN128_G2_DECOMPRESS_COST, ALT_BN128_MULTIPLICATION_COST,
+        ALT_BN128_PAIRING_ONE_PAIR_COST_FIRST, ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER,
+        SHA256_BASE_COST, SYSCALL_BASE_COST,
+    },
+    SolanaContext,
+};
+use ark_bn254::{Bn254, Fq12, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{BigInteger256, One};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+// Group operations accepted by sol_alt_bn128_group_op
+pub const ALT_BN128_ADD: u64 = 0;
+pub const ALT_BN128_MUL: u64 = 2;
+pub const ALT_BN128_PAIRING: u64 = 3;
+
+// Operations accepted by sol_alt_bn128_compression
+pub const ALT_BN128_G1_COMPRESS: u64 = 0;
+pub const ALT_BN128_G1_DECOMPRESS: u64 = 1;
+pub const ALT_BN128_G2_COMPRESS: u64 = 2;
+pub const ALT_BN128_G2_DECOMPRESS: u64 = 3;
+
+const FIELD_SIZE: usize = 32;
+const G1_SIZE: usize = 64;
+const G2_SIZE: usize = 128;
+const G1_COMPRESSED_SIZE: usize = 32;
+const G2_COMPRESSED_SIZE: usize = 64;
+
+pub const ALT_BN128_ADDITION_INPUT_LEN: usize = 128;
+pub const ALT_BN128_MULTIPLICATION_INPUT_LEN: usize = 96;
+pub const ALT_BN128_PAIRING_ELEMENT_LEN: usize = G1_SIZE + G2_SIZE;
+pub const ALT_BN128_ADDITION_OUTPUT_LEN: usize = G1_SIZE;
+pub const ALT_BN128_MULTIPLICATION_OUTPUT_LEN: usize = G1_SIZE;
+pub const ALT_BN128_PAIRING_OUTPUT_LEN: usize = 32;
+
+// Error codes returned by sol_alt_bn128_group_op, matching Solana's
+const INVALID_INPUT_DATA: u64 = 1;
+const GROUP_ERROR: u64 = 2;
+const SLICE_OUT_OF_BOUNDS: u64 = 3;
+
+// Error codes returned by sol_alt_bn128_compression, matching Solana's
+const G1_DECOMPRESSION_FAILED: u64 = 1;
+const G2_DECOMPRESSION_FAILED: u64 = 2;
+const G1_COMPRESSION_FAILED: u64 = 3;
+const G2_COMPRESSION_FAILED: u64 = 4;
+const INVALID_INPUT_SIZE: u64 = 5;
+
+/// Point operation on encoded input, returning the encoded output or an error code.
+type Calculation = fn(&[u8]) -> Result<Vec<u8>, u64>;
+
+/// Reverses the byte order of every `chunk`-sized value, converting between EIP-197
+/// big-endian and arkworks little-endian encodings. Reversing a whole 64-byte G2
+/// coordinate also turns EIP-197's `x1 | x0` order into arkworks' `x0 | x1`.
+fn convert_endianness(bytes: &[u8], chunk: usize) -> Vec<u8> {
+    bytes
+        .chunks(chunk)
+        .flat_map(|field| field.iter().rev().copied())
+        .collect()
+}
+
+/// Parses a big-endian G1 point, with all zeroes standing for the point at infinity.
+fn g1_from_be_bytes(bytes: &[u8]) -> Result<G1Affine, u64> {
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::deserialize_with_mode(
+        convert_endianness(bytes, FIELD_SIZE).as_slice(),
+        Compress::No,
+        Validate::Yes,
+    )
+    .map_err(|_| INVALID_INPUT_DATA)?;
+    if !point.is_on_curve() {
+        return Err(GROUP_ERROR);
+    }
+    Ok(point)
+}
+
+/// Parses a big-endian G2 point, with all zeroes standing for the point at infinity.
+fn g2_from_be_bytes(bytes: &[u8]) -> Result<G2Affine, u64> {
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(G2Affine::zero());
+    }
+    let point = G2Affine::deserialize_with_mode(
+        convert_endianness(bytes, 2 * FIELD_SIZE).as_slice(),
+        Compress::No,
+        Validate::Yes,
+    )
+    .map_err(|_| INVALID_INPUT_DATA)?;
+    if !point.is_on_curve() {
+        return Err(GROUP_ERROR);
+    }
+    Ok(point)
+}
+
+/// Encodes a G1 point as big-endian `x | y`; the point at infinity encodes as all zeroes.
+fn g1_to_be_bytes(point: &G1Affine) -> Result<Vec<u8>, u64> {
+    let mut bytes = [0u8; G1_SIZE];
+    point
+        .x
+        .serialize_with_mode(&mut bytes[..FIELD_SIZE], Compress::No)
+        .map_err(|_| INVALID_INPUT_DATA)?;
+    point
+        .y
+        .serialize_with_mode(&mut bytes[FIELD_SIZE..], Compress::No)
+        .map_err(|_| INVALID_INPUT_DATA)?;
+    Ok(convert_endianness(&bytes, FIELD_SIZE))
+}
+
+/// Adds two G1 points. Short input is zero padded, as with the EIP-196 precompile.
+pub fn alt_bn128_addition(input: &[u8]) -> Result<Vec<u8>, u64> {
+    if input.len() > ALT_BN128_ADDITION_INPUT_LEN {
+        return Err(INVALID_INPUT_DATA);
+    }
+    let mut input = input.to_vec();
+    input.resize(ALT_BN128_ADDITION_INPUT_LEN, 0);
+
+    let p = g1_from_be_bytes(&input[..G1_SIZE])?;
+    let q = g1_from_be_bytes(&input[G1_SIZE..])?;
+    g1_to_be_bytes(&(p + q).into())
+}
+
+/// Multiplies a G1 point by a big-endian 256-bit scalar, which is not reduced
+/// modulo the group order. Short input is zero padded.
+pub fn alt_bn128_multiplication(input: &[u8]) -> Result<Vec<u8>, u64> {
+    if input.len() > ALT_BN128_MULTIPLICATION_INPUT_LEN {
+        return Err(INVALID_INPUT_DATA);
+    }
+    let mut input = input.to_vec();
+    input.resize(ALT_BN128_MULTIPLICATION_INPUT_LEN, 0);
+
+    let p = g1_from_be_bytes(&input[..G1_SIZE])?;
+    let scalar = BigInteger256::deserialize_uncompressed_unchecked(
+        convert_endianness(&input[G1_SIZE..], FIELD_SIZE).as_slice(),
+    )
+    .map_err(|_| INVALID_INPUT_DATA)?;
+    g1_to_be_bytes(&p.mul_bigint(scalar).into())
+}
+
+/// Checks whether the product of pairings of `(G1, G2)` pairs is one, returning
+/// a big-endian 1 or 0. Trailing bytes short of a full pair are ignored, as on chain.
+pub fn alt_bn128_pairing(input: &[u8]) -> Result<Vec<u8>, u64> {
+    let pairs = (0..input.len() / ALT_BN128_PAIRING_ELEMENT_LEN)
+        .map(|index| {
+            let pair =
+                &input[index * ALT_BN128_PAIRING_ELEMENT_LEN..][..ALT_BN128_PAIRING_ELEMENT_LEN];
+            Ok((
+                g1_from_be_bytes(&pair[..G1_SIZE])?,
+                g2_from_be_bytes(&pair[G1_SIZE..])?,
+            ))
+        })
+        .collect::<Result<Vec<(G1Affine, G2Affine)>, u64>>()?;
+
+    let product = Bn254::multi_pairing(
+        pairs.iter().map(|pair| pair.0),
+        pairs.iter().map(|pair| pair.1),
+    );
+    let mut output = vec![0u8; ALT_BN128_PAIRING_OUTPUT_LEN];
+    if product.0 == Fq12::one() {
+        output[ALT_BN128_PAIRING_OUTPUT_LEN - 1] = 1;
+    }
+    Ok(output)
+}
+
+/// Compresses a big-endian G1 point to its 32-byte x coordinate and sign flag.
+pub fn alt_bn128_g1_compress(input: &[u8]) -> Result<Vec<u8>, u64> {
+    if input.len() != G1_SIZE {
+        return Err(INVALID_INPUT_SIZE);
+    }
+    if input.iter().all(|b| *b == 0) {
+        return Ok(vec![0u8; G1_COMPRESSED_SIZE]);
+    }
+    let point = G1Affine::deserialize_with_mode(
+        convert_endianness(input, FIELD_SIZE).as_slice(),
+        Compress::No,
+        Validate::No,
+    )
+    .map_err(|_| G1_COMPRESSION_FAILED)?;
+    let mut compressed = [0u8; G1_COMPRESSED_SIZE];
+    point
+        .serialize_compressed(compressed.as_mut_slice())
+        .map_err(|_| G1_COMPRESSION_FAILED)?;
+    Ok(convert_endianness(&compressed, FIELD_SIZE))
+}
+
+/// Recovers a big-endian G1 point from its compressed form.
+pub fn alt_bn128_g1_decompress(input: &[u8]) -> Result<Vec<u8>, u64> {
+    if input.len() != G1_COMPRESSED_SIZE {
+        return Err(INVALID_INPUT_SIZE);
+    }
+    if input.iter().all(|b| *b == 0) {
+        return Ok(vec![0u8; G1_SIZE]);
+    }
+    let point = G1Affine::deserialize_with_mode(
+        convert_endianness(input, FIELD_SIZE).as_slice(),
+        Compress::Yes,
+        Validate::No,
+    )
+    .map_err(|_| G1_DECOMPRESSION_FAILED)?;
+    g1_to_be_bytes(&point).map_err(|_| G1_DECOMPRESSION_FAILED)
+}
+
+/// Compresses a big-endian G2 point to its 64-byte x coordinate and sign flag.
+pub fn alt_bn128_g2_compress(input: &[u8]) -> Result<Vec<u8>, u64> {
+    if input.len() != G2_SIZE {
+        return Err(INVALID_INPUT_SIZE);
+    }
+    if input.iter().all(|b| *b == 0) {
+        return Ok(vec![0u8; G2_COMPRESSED_SIZE]);
+    }
+    let point = G2Affine::deserialize_with_mode(
+        convert_endianness(input, 2 * FIELD_SIZE).as_slice(),
+        Compress::No,
+        Validate::No,
+    )
+    .map_err(|_| G2_COMPRESSION_FAILED)?;
+    let mut compressed = [0u8; G2_COMPRESSED_SIZE];
+    point
+        .serialize_compressed(compressed.as_mut_slice())
+        .map_err(|_| G2_COMPRESSION_FAILED)?;
+    Ok(convert_endianness(&compressed, 2 * FIELD_SIZE))
+}
+
+/// Recovers a big-endian G2 point from its compressed form.
+pub fn alt_bn128_g2_decompress(input: &[u8]) -> Result<Vec<u8>, u64> {
+    if input.len() != G2_COMPRESSED_SIZE {
+        return Err(INVALID_INPUT_SIZE);
+    }
+    if input.iter().all(|b| *b == 0) {
+        return Ok(vec![0u8; G2_SIZE]);
+    }
+    let point = G2Affine::deserialize_with_mode(
+        convert_endianness(input, 2 * FIELD_SIZE).as_slice(),
+        Compress::Yes,
+        Validate::No,
+    )
+    .map_err(|_| G2_DECOMPRESSION_FAILED)?;
+    let mut bytes = [0u8; G2_SIZE];
+    point
+        .x
+        .serialize_with_mode(&mut bytes[..2 * FIELD_SIZE], Compress::No)
+        .map_err(|_| G2_DECOMPRESSION_FAILED)?;
+    point
+        .y
+        .serialize_with_mode(&mut bytes[2 * FIELD_SIZE..], Compress::No)
+        .map_err(|_| G2_DECOMPRESSION_FAILED)?;
+    Ok(convert_endianness(&bytes, 2 * FIELD_SIZE))
+}
+
+// Implements sol_alt_bn128_group_op: G1 addition, G1 scalar multiplication and
+// the pairing check. Unknown operations abort the program; calculation failures
+// are returned to it as Solana's error codes.
+declare_builtin_function!(
+    SyscallAltBn128,
+    fn rust(
+        context: &mut SolanaContext,
+        group_op: u64,
+        input_addr: u64,
+        input_size: u64,
+        result_addr: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let (cost, output_len, calculation): (u64, usize, Calculation) = match group_op {
+            ALT_BN128_ADD => (
+                ALT_BN128_ADDITION_COST,
+                ALT_BN128_ADDITION_OUTPUT_LEN,
+                alt_bn128_addition,
+            ),
+            ALT_BN128_MUL => (
+                ALT_BN128_MULTIPLICATION_COST,
+                ALT_BN128_MULTIPLICATION_OUTPUT_LEN,
+                alt_bn128_multiplication,
+            ),
+            ALT_BN128_PAIRING => {
+                let pairs = input_size / ALT_BN128_PAIRING_ELEMENT_LEN as u64;
+                let cost = ALT_BN128_PAIRING_ONE_PAIR_COST_FIRST
+                    .saturating_add(
+                        ALT_BN128_PAIRING_ONE_PAIR_COST_OTHER
+                            .saturating_mul(pairs.saturating_sub(1)),
+                    )
+                    .saturating_add(SHA256_BASE_COST)
+                    .saturating_add(input_size)
+                    .saturating_add(ALT_BN128_PAIRING_OUTPUT_LEN as u64);
+                (cost, ALT_BN128_PAIRING_OUTPUT_LEN, alt_bn128_pairing)
+            }
+            _ => return Err(format!("Invalid alt_bn128 group operation {}", group_op).into()),
+        };
+        context.consume_checked(cost)?;
+
+        let input = translate_slice(memory_mapping, input_addr, input_size)?;
+        let result = translate_slice_mut(memory_mapping, result_addr, output_len as u64)?;
+
+        match calculation(input) {
+            Ok(point) if point.len() == output_len => {
+                result.copy_from_slice(&point);
+                Ok(0)
+            }
+            Ok(_) => Ok(SLICE_OUT_OF_BOUNDS),
+            Err(code) => Ok(code),
+        }
+    }
+);
+
+// Implements sol_alt_bn128_compression for converting G1 and G2 points to and
+// from their compressed encodings.
+declare_builtin_function!(
+    SyscallAltBn128Compression,
+    fn rust(
+        context: &mut SolanaContext,
+        op: u64,
+        input_addr: u64,
+        input_size: u64,
+        result_addr: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let (cost, output_len, calculation): (u64, usize, Calculation) = match op {
+            ALT_BN128_G1_COMPRESS => (
+                ALT_BN128_G1_COMPRESS_COST,
+                G1_COMPRESSED_SIZE,
+                alt_bn128_g1_compress,
+            ),
+            ALT_BN128_G1_DECOMPRESS => (
+                ALT_BN128_G1_DECOMPRESS_COST,
+                G1_SIZE,
+                alt_bn128_g1_decompress,
+            ),
+            ALT_BN128_G2_COMPRESS => (
+                ALT_BN128_G2_COMPRESS_COST,
+                G2_COMPRESSED_SIZE,
+                alt_bn128_g2_compress,
+            ),
+            ALT_BN128_G2_DECOMPRESS => (
+                ALT_BN128_G2_DECOMPRESS_COST,
+                G2_SIZE,
+                alt_bn128_g2_decompress,
+            ),
+            _ => return Err(format!("Invalid alt_bn128 compression operation {}", op).into()),
+        };
+        context.consume_checked(SYSCALL_BASE_COST.saturating_add(cost))?;
+
+        let input = translate_slice(memory_mapping, input_addr, input_size)?;
+        let result = translate_slice_mut(memory_mapping, result_addr, output_len as u64)?;
+
+        match calculation(input) {
+            Ok(point) => {
+                result.copy_from_slice(&point);
+                Ok(0)
+            }
+            Err(code) => Ok(code),
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROUP_ORDER_MINUS_ONE: &str =
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000";
+
+    // Known-answer vectors from the Ethereum EIP-196/EIP-197 precompile test suite,
+    // as used by Solana's alt_bn128 implementation
+    const ADDITION_VECTORS: &[(&str, &str, &str)] = &[
+        (
+            "chfast1",
+            "\
+            18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9063c909c4720840cb5134cb9\
+            f59fa749755796819658d32efc0d288198f3726607c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a\
+            88019e7c6a014eed06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7",
+            "\
+            2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703301d1d33be6da8e509df21cc\
+            35964723180eed7532537db9ae5e7d48f195c915",
+        ),
+        (
+            "chfast2",
+            "\
+            2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703301d1d33be6da8e509df21cc\
+            35964723180eed7532537db9ae5e7d48f195c91518b18acfb4c2c30276db5411368e7185b311dd124691610c\
+            5d3b74034e093dc9063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266",
+            "\
+            2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb721611ce0a6af85915e2f1d70\
+            300909ce2e49dfad4a4619c8390cae66cefdb204",
+        ),
+        (
+            "cdetrio1",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+            00000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000",
+        ),
+        (
+            "cdetrio4",
+            "",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000",
+        ),
+        (
+            "cdetrio8",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000\
+            0000000000000000000000000000000000000002000000000000000000000000000000000000000000000000\
+            00000000000000010000000000000000000000000000000000000000000000000000000000000002",
+            "\
+            030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd315ed738c0e0a7c92e7845f96\
+            b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+        ),
+        (
+            "cdetrio9",
+            "\
+            17c139df0efee0f766bc0204762b774362e4ded88953a39ce849a8a7fa163fa901e0559bacb160664764a357\
+            af8a9fe70baa9258e0b959273ffc5718c6d4cc7c039730ea8dff1254c0fee9c0ea777d29a9c710b7e616683f\
+            194f18c43b43b869073a5ffcc6fc7a28c30723d6e58ce577356982d65b833a5a5c15bf9024b43d98",
+            "\
+            15bf2bb17880144b5d1cd2b1f46eff9d617bffd1ca57c37fb5a49bd84e53cf66049c797f9ce0d17083deb32b\
+            5e36f2ea2a212ee036598dd7624c168993d1355f",
+        ),
+    ];
+
+    const MULTIPLICATION_VECTORS: &[(&str, &str, &str)] = &[
+        (
+            "chfast1",
+            "\
+            2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb721611ce0a6af85915e2f1d70\
+            300909ce2e49dfad4a4619c8390cae66cefdb204000000000000000000000000000000000000000000000000\
+            11138ce750fa15c2",
+            "\
+            070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c031b8ce914eba3a9ffb989f9\
+            cdd5b0f01943074bf4f0f315690ec3cec6981afc",
+        ),
+        (
+            "chfast2",
+            "\
+            070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c031b8ce914eba3a9ffb989f9\
+            cdd5b0f01943074bf4f0f315690ec3cec6981afc30644e72e131a029b85045b68181585d97816a916871ca8d\
+            3c208c16d87cfd46",
+            "\
+            025a6f4181d2b4ea8b724290ffb40156eb0adb514c688556eb79cdea0752c2bb2eff3f31dea215f1eb86023a\
+            133a996eb6300b44da664d64251d05381bb8a02e",
+        ),
+        (
+            "chfast3",
+            "\
+            025a6f4181d2b4ea8b724290ffb40156eb0adb514c688556eb79cdea0752c2bb2eff3f31dea215f1eb86023a\
+            133a996eb6300b44da664d64251d05381bb8a02e183227397098d014dc2822db40c0ac2ecbc0b548b438e546\
+            9e10460b6c3e7ea3",
+            "\
+            14789d0d4a730b354403b5fac948113739e276c23e0258d8596ee72f9cd9d3230af18a63153e0ec25ff9f295\
+            1dd3fa90ed0197bfef6e2a1a62b5095b9d2b4a27",
+        ),
+        (
+            "cdetrio1",
+            "\
+            1a87b0584ce92f4593d161480614f2989035225609f08058ccfa3d0f940febe31a2f3c951f6dadcc7ee9007d\
+            ff81504b0fcd6d7cf59996efdc33d92bf7f9f8f6ffffffffffffffffffffffffffffffffffffffffffffffff\
+            ffffffffffffffff",
+            "\
+            2cde5879ba6f13c0b5aa4ef627f159a3347df9722efce88a9afbb20b763b4c411aa7e43076f6aee272755a7f\
+            9b84832e71559ba0d2e0b17d5f9f01755e5b0d11",
+        ),
+        (
+            "cdetrio6",
+            "\
+            17c139df0efee0f766bc0204762b774362e4ded88953a39ce849a8a7fa163fa901e0559bacb160664764a357\
+            af8a9fe70baa9258e0b959273ffc5718c6d4cc7cffffffffffffffffffffffffffffffffffffffffffffffff\
+            ffffffffffffffff",
+            "\
+            29e587aadd7c06722aabba753017c093f70ba7eb1f1c0104ec0564e7e3e21f6022b1143f6a41008e7755c71c\
+            3d00b6b915d386de21783ef590486d8afa8453b1",
+        ),
+        (
+            "cdetrio11",
+            "\
+            039730ea8dff1254c0fee9c0ea777d29a9c710b7e616683f194f18c43b43b869073a5ffcc6fc7a28c30723d6\
+            e58ce577356982d65b833a5a5c15bf9024b43d98ffffffffffffffffffffffffffffffffffffffffffffffff\
+            ffffffffffffffff",
+            "\
+            00a1a234d08efaa2616607e31eca1980128b00b415c845ff25bba3afcb81dc00242077290ed33906aeb8e42f\
+            d98c41bcb9057ba03421af3f2d08cfc441186024",
+        ),
+    ];
+
+    const PAIRING_VECTORS: &[(&str, &str, &str)] = &[
+        (
+            "jeff1",
+            "\
+            1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f593034dd2920f673e204fee281\
+            1c678745fc819b55d3e9d294e45c9b03a76aef41209dd15ebff5d46c4bd888e51a93cf99a7329636c6351439\
+            6b4a452003a35bf704bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a416782bb8324a\
+            f6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d120a2a4cf30c1bf9845f20c6fe39e07e\
+            a2cce61f0c9bb048165fe5e4de877550111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49\
+            d1b6df7c2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411198e9393920d483a\
+            7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4\
+            f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+            12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        ),
+        (
+            "jeff6",
+            "\
+            1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f593034dd2920f673e204fee281\
+            1c678745fc819b55d3e9d294e45c9b03a76aef41209dd15ebff5d46c4bd888e51a93cf99a7329636c6351439\
+            6b4a452003a35bf704bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a416782bb8324a\
+            f6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d120a2a4cf30c1bf9845f20c6fe39e07e\
+            a2cce61f0c9bb048165fe5e4de877550111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49\
+            d1b6df7c103188585e2364128fe25c70558f1560f4f9350baf3959e603cc91486e110936198e9393920d483a\
+            7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4\
+            f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+            12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            "empty_data",
+            "",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        ),
+        (
+            "one_point",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000\
+            0000000000000000000000000000000000000002198e9393920d483a7260bfb731fb5d25f1aa493335a9e712\
+            97e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0\
+            585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408f\
+            e3d1e7690c43d37b4ce6cc0166fa7daa",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            "two_point_match_2",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000\
+            0000000000000000000000000000000000000002198e9393920d483a7260bfb731fb5d25f1aa493335a9e712\
+            97e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0\
+            585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408f\
+            e3d1e7690c43d37b4ce6cc0166fa7daa00000000000000000000000000000000000000000000000000000000\
+            000000010000000000000000000000000000000000000000000000000000000000000002198e9393920d483a\
+            7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4\
+            f75edadd46debd5cd992f6ed275dc4a288d1afb3cbb1ac09187524c7db36395df7be3b99e673b13a075a65ec\
+            1d9befcd05a5323e6da4d435f3b617cdb3af83285c2df711ef39c01571827f9d",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        ),
+        (
+            "two_point_match_3",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000\
+            0000000000000000000000000000000000000002203e205db4f19b37b60121b83a7333706db86431c6d83584\
+            9957ed8c3928ad7927dc7234fd11d3e8c36c59277c3e6f149d5cd3cfa9a62aee49f8130962b4b3b9195e8aa5\
+            b7827463722b8c153931579d3505566b4edf48d498e185f0509de15204bb53b8977e5f92a0bc372742c48309\
+            44a59b4fe6b1c0466e2a6dad122b5d2e030644e72e131a029b85045b68181585d97816a916871ca8d3c208c1\
+            6d87cfd31a76dae6d3272396d0cbe61fced2bc532edac647851e3ac53ce1cc9c7e645a83198e9393920d483a\
+            7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4\
+            f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+            12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+            "\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        ),
+    ];
+
+    fn run_vectors(vectors: &[(&str, &str, &str)], calculation: Calculation) {
+        for (name, input, expected) in vectors {
+            let output = calculation(&hex::decode(input).unwrap())
+                .unwrap_or_else(|code| panic!("{} failed with error {}", name, code));
+            assert_eq!(hex::encode(output), *expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn addition_vectors() {
+        run_vectors(ADDITION_VECTORS, alt_bn128_addition);
+    }
+
+    #[test]
+    fn multiplication_vectors() {
+        run_vectors(MULTIPLICATION_VECTORS, alt_bn128_multiplication);
+    }
+
+    #[test]
+    fn pairing_vectors() {
+        run_vectors(PAIRING_VECTORS, alt_bn128_pairing);
+    }
+
+    #[test]
+    fn pairing_ignores_trailing_bytes() {
+        let mut expected = vec![0u8; 32];
+        expected[31] = 1;
+        assert_eq!(alt_bn128_pairing(&[0u8; 193]), Ok(expected));
+    }
+
+    #[test]
+    fn group_op_error_codes() {
+        assert_eq!(alt_bn128_addition(&[0u8; 129]), Err(INVALID_INPUT_DATA));
+        assert_eq!(
+            alt_bn128_multiplication(&[0u8; 97]),
+            Err(INVALID_INPUT_DATA)
+        );
+        // (1, 3) is not on the curve
+        let mut point = [0u8; 64];
+        point[31] = 1;
+        point[63] = 3;
+        assert_eq!(alt_bn128_addition(&point), Err(INVALID_INPUT_DATA));
+    }
+
+    #[test]
+    fn g1_compression_round_trip() {
+        let g1 = hex::decode(
+            "2dceffa69837808a4fd991a4194a78eaead94495a22c8578b8cd0c2caf62a8ac\
+             1418d80fd1af6a4b93ec5a657bdbf597d1cada68940820fef3bfda7a2a51c154",
+        )
+        .unwrap();
+        let compressed = alt_bn128_g1_compress(&g1).unwrap();
+        assert_eq!(compressed.len(), 32);
+        assert_eq!(alt_bn128_g1_decompress(&compressed).unwrap(), g1);
+
+        let negated = alt_bn128_multiplication(
+            &[g1.as_slice(), &hex::decode(GROUP_ORDER_MINUS_ONE).unwrap()].concat(),
+        )
+        .unwrap();
+        let compressed = alt_bn128_g1_compress(&negated).unwrap();
+        assert_eq!(alt_bn128_g1_decompress(&compressed).unwrap(), negated);
+    }
+
+    #[test]
+    fn g2_compression_round_trip() {
+        // The G2 generator in EIP-197 encoding, as used by the pairing vectors
+        let g2 = hex::decode(
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+             090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        )
+        .unwrap();
+        let compressed = alt_bn128_g2_compress(&g2).unwrap();
+        assert_eq!(compressed.len(), 64);
+        assert_eq!(alt_bn128_g2_decompress(&compressed).unwrap(), g2);
+    }
+
+    #[test]
+    fn compression_error_codes() {
+        assert_eq!(alt_bn128_g1_compress(&[0u8; 63]), Err(INVALID_INPUT_SIZE));
+        assert_eq!(alt_bn128_g2_decompress(&[0u8; 65]), Err(INVALID_INPUT_SIZE));
+        assert_eq!(alt_bn128_g1_decompress(&[0u8; 32]), Ok(vec![0u8; 64]));
+        assert_eq!(alt_bn128_g2_compress(&[0u8; 128]), Ok(vec![0u8; 64]));
+        assert_eq!(
+            alt_bn128_g1_decompress(&[0xff; 32]),
+            Err(G1_DECOMPRESSION_FAILED)
+        );
+    }
+}
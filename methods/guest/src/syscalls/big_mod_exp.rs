@@ -0,0 +1,230 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Modular exponentiation over big-endian unsigned integers of up to 4096 bits,
+//! as used for RSA signature checks and VDF verification.
+
+use super::{translate_slice, translate_slice_mut};
+use crate::{
+    compute_budget::{
+        BIG_MODULAR_EXPONENTIATION_BASE_COST, BIG_MODULAR_EXPONENTIATION_COST_DIVISOR,
+        SYSCALL_BASE_COST,
+    },
+    SolanaContext,
+};
+use num_bigint::BigUint;
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+/// Maximum length in bytes of the base, exponent and modulus.
+pub const MAX_INPUT_LEN: u64 = 512;
+
+/// Size of `BigModExpParams` in VM memory.
+const PARAMS_SIZE: u64 = 6 * size_of::<u64>() as u64;
+
+/// Solana's `BigModExpParams`: a pointer and length for each of the base,
+/// exponent and modulus, laid out as six little-endian u64s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BigModExpParams {
+    base: u64,
+    base_len: u64,
+    exponent: u64,
+    exponent_len: u64,
+    modulus: u64,
+    modulus_len: u64,
+}
+
+impl BigModExpParams {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let field = |index: usize| {
+            u64::from_le_bytes(
+                bytes[index * size_of::<u64>()..][..size_of::<u64>()]
+                    .try_into()
+                    .expect("8 bytes"),
+            )
+        };
+        Self {
+            base: field(0),
+            base_len: field(1),
+            exponent: field(2),
+            exponent_len: field(3),
+            modulus: field(4),
+            modulus_len: field(5),
+        }
+    }
+}
+
+/// Computes `base^exponent mod modulus`, left padded with zeros to the length of
+/// the modulus. A modulus of zero or one yields all zeros.
+pub fn big_mod_exp(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+    let modulus_len = modulus.len();
+    let base = BigUint::from_bytes_be(base);
+    let exponent = BigUint::from_bytes_be(exponent);
+    let modulus = BigUint::from_bytes_be(modulus);
+    if modulus <= BigUint::from(1u8) {
+        return vec![0; modulus_len];
+    }
+
+    let result = mod_pow(&base, &exponent, &modulus).to_bytes_be();
+    let mut padded = vec![0; modulus_len.saturating_sub(result.len())];
+    padded.extend(result);
+    padded
+}
+
+/// Moduli that fit the zkVM bigint circuit are exponentiated with accelerated
+/// 256-bit modular multiplication; anything wider falls back to `num-bigint`.
+#[cfg(target_os = "zkvm")]
+fn mod_pow(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+    if modulus.bits() <= 256 {
+        accelerated::mod_pow(base, exponent, modulus)
+    } else {
+        base.modpow(exponent, modulus)
+    }
+}
+
+#[cfg(not(target_os = "zkvm"))]
+fn mod_pow(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+    base.modpow(exponent, modulus)
+}
+
+#[cfg(target_os = "zkvm")]
+mod accelerated {
+    use num_bigint::BigUint;
+    use risc0_zkvm_platform::syscall::{bigint, sys_bigint};
+
+    type Words = [u32; bigint::WIDTH_WORDS];
+
+    fn to_words(value: &BigUint) -> Words {
+        let mut words = [0; bigint::WIDTH_WORDS];
+        for (word, digit) in words.iter_mut().zip(value.to_u32_digits()) {
+            *word = digit;
+        }
+        words
+    }
+
+    fn mul_mod(x: &Words, y: &Words, modulus: &Words) -> Words {
+        let mut result = [0; bigint::WIDTH_WORDS];
+        unsafe { sys_bigint(&mut result, bigint::OP_MULTIPLY, x, y, modulus) };
+        result
+    }
+
+    /// Left-to-right square and multiply. The base is reduced first since the
+    /// circuit requires its inputs to be smaller than the modulus.
+    pub fn mod_pow(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        let modulus_words = to_words(modulus);
+        let base = to_words(&(base % modulus));
+        let mut result = to_words(&BigUint::from(1u8));
+        for bit in (0..exponent.bits()).rev() {
+            result = mul_mod(&result, &result, &modulus_words);
+            if exponent.bit(bit) {
+                result = mul_mod(&result, &base, &modulus_words);
+            }
+        }
+        BigUint::from_slice(&result)
+    }
+}
+
+// Implements sol_big_mod_exp. Reads Solana's BigModExpParams, charges
+// base + max_len^2 / divisor and writes a result as long as the modulus.
+declare_builtin_function!(
+    SyscallBigModExp,
+    fn rust(
+        context: &mut SolanaContext,
+        params_addr: u64,
+        result_addr: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let params =
+            BigModExpParams::from_bytes(translate_slice(memory_mapping, params_addr, PARAMS_SIZE)?);
+        if params.base_len > MAX_INPUT_LEN
+            || params.exponent_len > MAX_INPUT_LEN
+            || params.modulus_len > MAX_INPUT_LEN
+        {
+            return Err("Invalid length".into());
+        }
+
+        let input_len = params
+            .base_len
+            .max(params.exponent_len)
+            .max(params.modulus_len);
+        context.consume_checked(
+            SYSCALL_BASE_COST
+                .saturating_add(
+                    input_len.saturating_mul(input_len) / BIG_MODULAR_EXPONENTIATION_COST_DIVISOR,
+                )
+                .saturating_add(BIG_MODULAR_EXPONENTIATION_BASE_COST),
+        )?;
+
+        let base = translate_slice(memory_mapping, params.base, params.base_len)?;
+        let exponent = translate_slice(memory_mapping, params.exponent, params.exponent_len)?;
+        let modulus = translate_slice(memory_mapping, params.modulus, params.modulus_len)?;
+        let value = big_mod_exp(base, exponent, modulus);
+
+        let result = translate_slice_mut(memory_mapping, result_addr, params.modulus_len)?;
+        result.copy_from_slice(&value);
+        Ok(0)
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_values() {
+        assert_eq!(big_mod_exp(&[3], &[5], &[7]), vec![5]);
+        assert_eq!(big_mod_exp(&[2], &[0], &[0, 0, 9]), vec![0, 0, 1]);
+        assert_eq!(big_mod_exp(&[], &[], &[5]), vec![1]);
+    }
+
+    #[test]
+    fn degenerate_modulus() {
+        assert_eq!(big_mod_exp(&[3], &[5], &[0, 0, 0]), vec![0; 3]);
+        assert_eq!(big_mod_exp(&[3], &[5], &[0, 1]), vec![0; 2]);
+        assert_eq!(big_mod_exp(&[3], &[5], &[]), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn eip198_fermat() {
+        // 3^(p-1) mod p for the secp256k1 field prime, from EIP-198
+        let modulus =
+            hex::decode("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+                .unwrap();
+        let exponent =
+            hex::decode("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e")
+                .unwrap();
+        let mut expected = vec![0; 32];
+        expected[31] = 1;
+        assert_eq!(big_mod_exp(&[3], &exponent, &modulus), expected);
+    }
+
+    #[test]
+    fn wide_modulus() {
+        // (2^4095 + 1)^2 mod 2^4095 = 1
+        let mut modulus = vec![0; MAX_INPUT_LEN as usize];
+        modulus[0] = 0x80;
+        let mut base = modulus.clone();
+        base[MAX_INPUT_LEN as usize - 1] = 1;
+        let mut expected = vec![0; MAX_INPUT_LEN as usize];
+        expected[MAX_INPUT_LEN as usize - 1] = 1;
+        assert_eq!(big_mod_exp(&base, &[2], &modulus), expected);
+    }
+
+    #[test]
+    fn params_layout() {
+        let bytes: Vec<u8> = (1u64..=6).flat_map(u64::to_le_bytes).collect();
+        assert_eq!(
+            BigModExpParams::from_bytes(&bytes),
+            BigModExpParams {
+                base: 1,
+                base_len: 2,
+                exponent: 3,
+                exponent_len: 4,
+                modulus: 5,
+                modulus_len: 6,
+            }
+        );
+    }
+}
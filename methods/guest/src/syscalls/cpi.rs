@@ -0,0 +1,995 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Cross-program invocation. sol_invoke_signed_rust and sol_invoke_signed_c differ only
+//! in how the instruction and account infos are laid out in program memory. Both then
+//! check the privileges the caller grants, sync the caller's account changes, run the
+//! callee on the invoke stack and write the accounts it changed back to the caller.
+
+use super::{translate_slice, translate_slice_mut, translate_slices, Error};
+use crate::{
+    account_rules,
+    compute_budget::{CPI_BYTES_PER_UNIT, INVOKE_UNITS},
+    invoke::{self, InvokeFrame, MAX_INVOKE_STACK_HEIGHT},
+    serializer::MAX_PERMITTED_DATA_INCREASE,
+    system_program, SolanaContext,
+};
+use runtime::{pda::MAX_SEEDS, Account, Pubkey};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+/// Maximum instruction data passed to an invoked program.
+const MAX_CPI_INSTRUCTION_DATA_LEN: u64 = 10 * 1024;
+/// Maximum accounts referenced by an invoked instruction.
+const MAX_CPI_INSTRUCTION_ACCOUNTS: u64 = u8::MAX as u64;
+/// Maximum account infos passed alongside an invoked instruction.
+const MAX_CPI_ACCOUNT_INFOS: u64 = 128;
+/// Maximum program addresses the caller can sign for in one invocation.
+const MAX_SIGNERS: u64 = 16;
+
+// Layouts of the Rust SDK's StableInstruction, AccountMeta and AccountInfo
+const RUST_INSTRUCTION_SIZE: u64 = 80;
+const RUST_ACCOUNT_META_SIZE: u64 = 34;
+const RUST_ACCOUNT_INFO_SIZE: u64 = 48;
+/// Offset of the value in an `Rc<RefCell<T>>` allocation, after the strong, weak and borrow counts.
+const RC_REF_CELL_VALUE_OFFSET: u64 = 24;
+
+// Layouts of the C SDK's SolInstruction, SolAccountMeta and SolAccountInfo
+const C_INSTRUCTION_SIZE: u64 = 40;
+const C_ACCOUNT_META_SIZE: u64 = 16;
+const C_ACCOUNT_INFO_SIZE: u64 = 56;
+/// Offset of `data_len` in a SolAccountInfo.
+const C_ACCOUNT_INFO_DATA_LEN_OFFSET: u64 = 16;
+
+/// An account referenced by the invoked instruction.
+struct AccountMeta {
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+/// The instruction the caller asked to invoke.
+struct Instruction {
+    program_id: Pubkey,
+    accounts: Vec<AccountMeta>,
+    data: Vec<u8>,
+}
+
+/// An account info in the caller's memory, locating the fields a callee may change.
+struct CallerAccount {
+    key: Pubkey,
+    lamports_addr: u64,
+    owner_addr: u64,
+    data_addr: u64,
+    data_len: u64,
+    /// Where the caller keeps the length of its data slice.
+    ref_to_len_addr: u64,
+}
+
+/// Reads a little-endian u64 at `offset` of a translated struct.
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(
+        bytes[offset..offset + size_of::<u64>()]
+            .try_into()
+            .expect("8 bytes"),
+    )
+}
+
+/// Maps a VM array of `len` structs of `size` bytes to one host slice per struct.
+fn translate_array<'a>(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+    size: u64,
+) -> Result<Vec<&'a [u8]>, Error> {
+    let bytes = translate_slice(memory_mapping, addr, len.saturating_mul(size))?;
+    let size = size as usize;
+    Ok((0..len as usize)
+        .map(|index| &bytes[index * size..(index + 1) * size])
+        .collect())
+}
+
+fn read_u64(memory_mapping: &MemoryMapping, addr: u64) -> Result<u64, Error> {
+    Ok(u64_at(
+        translate_slice(memory_mapping, addr, size_of::<u64>() as u64)?,
+        0,
+    ))
+}
+
+fn write_u64(memory_mapping: &MemoryMapping, addr: u64, value: u64) -> Result<(), Error> {
+    translate_slice_mut(memory_mapping, addr, size_of::<u64>() as u64)?
+        .copy_from_slice(&value.to_le_bytes());
+    Ok(())
+}
+
+fn read_pubkey(memory_mapping: &MemoryMapping, addr: u64) -> Result<Pubkey, Error> {
+    Ok(Pubkey::try_from(translate_slice(
+        memory_mapping,
+        addr,
+        size_of::<Pubkey>() as u64,
+    )?)?)
+}
+
+/// Bounds the instruction before its accounts and data are translated.
+fn check_instruction_size(accounts_len: u64, data_len: u64) -> Result<(), Error> {
+    if accounts_len > MAX_CPI_INSTRUCTION_ACCOUNTS {
+        return Err(format!(
+            "Too many instruction accounts ({} > {})",
+            accounts_len, MAX_CPI_INSTRUCTION_ACCOUNTS
+        )
+        .into());
+    }
+    if data_len > MAX_CPI_INSTRUCTION_DATA_LEN {
+        return Err(format!(
+            "Instruction data too large ({} > {})",
+            data_len, MAX_CPI_INSTRUCTION_DATA_LEN
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn check_account_infos(account_infos_len: u64) -> Result<(), Error> {
+    if account_infos_len > MAX_CPI_ACCOUNT_INFOS {
+        return Err(format!(
+            "Too many account infos ({} > {})",
+            account_infos_len, MAX_CPI_ACCOUNT_INFOS
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn translate_instruction_rust(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+) -> Result<Instruction, Error> {
+    let instruction = translate_slice(memory_mapping, addr, RUST_INSTRUCTION_SIZE)?;
+    // Each StableVec is a pointer, capacity and length
+    let accounts_addr = u64_at(instruction, 0);
+    let accounts_len = u64_at(instruction, 16);
+    let data_addr = u64_at(instruction, 24);
+    let data_len = u64_at(instruction, 40);
+    let program_id = Pubkey::try_from(&instruction[48..80])?;
+    check_instruction_size(accounts_len, data_len)?;
+
+    let accounts = translate_array(
+        memory_mapping,
+        accounts_addr,
+        accounts_len,
+        RUST_ACCOUNT_META_SIZE,
+    )?
+    .into_iter()
+    .map(|meta| -> Result<AccountMeta, Error> {
+        Ok(AccountMeta {
+            pubkey: Pubkey::try_from(&meta[..32])?,
+            is_signer: meta[32] != 0,
+            is_writable: meta[33] != 0,
+        })
+    })
+    .collect::<Result<_, _>>()?;
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data: translate_slice(memory_mapping, data_addr, data_len)?.to_vec(),
+    })
+}
+
+fn translate_account_infos_rust(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+) -> Result<Vec<CallerAccount>, Error> {
+    check_account_infos(len)?;
+    translate_array(memory_mapping, addr, len, RUST_ACCOUNT_INFO_SIZE)?
+        .into_iter()
+        .map(|info| -> Result<CallerAccount, Error> {
+            // Lamports and data sit behind an Rc<RefCell<&mut _>>
+            let lamports_rc = u64_at(info, 8);
+            let data_rc = u64_at(info, 16);
+            let data_value_addr = data_rc.saturating_add(RC_REF_CELL_VALUE_OFFSET);
+            let ref_to_len_addr = data_value_addr.saturating_add(size_of::<u64>() as u64);
+            Ok(CallerAccount {
+                key: read_pubkey(memory_mapping, u64_at(info, 0))?,
+                lamports_addr: read_u64(
+                    memory_mapping,
+                    lamports_rc.saturating_add(RC_REF_CELL_VALUE_OFFSET),
+                )?,
+                owner_addr: u64_at(info, 24),
+                data_addr: read_u64(memory_mapping, data_value_addr)?,
+                data_len: read_u64(memory_mapping, ref_to_len_addr)?,
+                ref_to_len_addr,
+            })
+        })
+        .collect()
+}
+
+fn translate_instruction_c(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+) -> Result<Instruction, Error> {
+    let instruction = translate_slice(memory_mapping, addr, C_INSTRUCTION_SIZE)?;
+    let program_id = read_pubkey(memory_mapping, u64_at(instruction, 0))?;
+    let accounts_addr = u64_at(instruction, 8);
+    let accounts_len = u64_at(instruction, 16);
+    let data_addr = u64_at(instruction, 24);
+    let data_len = u64_at(instruction, 32);
+    check_instruction_size(accounts_len, data_len)?;
+
+    let accounts = translate_array(
+        memory_mapping,
+        accounts_addr,
+        accounts_len,
+        C_ACCOUNT_META_SIZE,
+    )?
+    .into_iter()
+    .map(|meta| -> Result<AccountMeta, Error> {
+        Ok(AccountMeta {
+            pubkey: read_pubkey(memory_mapping, u64_at(meta, 0))?,
+            is_writable: meta[8] != 0,
+            is_signer: meta[9] != 0,
+        })
+    })
+    .collect::<Result<_, _>>()?;
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data: translate_slice(memory_mapping, data_addr, data_len)?.to_vec(),
+    })
+}
+
+fn translate_account_infos_c(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+) -> Result<Vec<CallerAccount>, Error> {
+    check_account_infos(len)?;
+    translate_array(memory_mapping, addr, len, C_ACCOUNT_INFO_SIZE)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, info)| -> Result<CallerAccount, Error> {
+            let info_addr = addr.saturating_add(index as u64 * C_ACCOUNT_INFO_SIZE);
+            Ok(CallerAccount {
+                key: read_pubkey(memory_mapping, u64_at(info, 0))?,
+                lamports_addr: u64_at(info, 8),
+                owner_addr: u64_at(info, 32),
+                data_addr: u64_at(info, 24),
+                data_len: u64_at(info, C_ACCOUNT_INFO_DATA_LEN_OFFSET as usize),
+                ref_to_len_addr: info_addr.saturating_add(C_ACCOUNT_INFO_DATA_LEN_OFFSET),
+            })
+        })
+        .collect()
+}
+
+/// Derives the program addresses the caller signs for. Rust and C lay out their
+/// signer seeds identically, as an array of `(addr, len)` arrays of seeds.
+fn translate_signers(
+    memory_mapping: &MemoryMapping,
+    addr: u64,
+    len: u64,
+    program_id: &Pubkey,
+) -> Result<Vec<Pubkey>, Error> {
+    if len > MAX_SIGNERS {
+        return Err(format!("Too many signers ({} > {})", len, MAX_SIGNERS).into());
+    }
+    translate_array(memory_mapping, addr, len, 2 * size_of::<u64>() as u64)?
+        .into_iter()
+        .map(|signer| -> Result<Pubkey, Error> {
+            let seeds_len = u64_at(signer, 8);
+            if seeds_len > MAX_SEEDS as u64 {
+                return Err(format!("Too many seeds ({} > {})", seeds_len, MAX_SEEDS).into());
+            }
+            let seeds = translate_slices(memory_mapping, u64_at(signer, 0), seeds_len)?;
+            Pubkey::create_program_address(&seeds, program_id)
+                .map_err(|e| format!("BadSeeds: {}", e).into())
+        })
+        .collect()
+}
+
+/// Records the caller's changes to an account before the callee sees it, holding
+/// them to the same rules as if the caller had returned.
+fn sync_caller_account(
+    context: &mut SolanaContext,
+    memory_mapping: &MemoryMapping,
+    caller_account: &CallerAccount,
+) -> Result<(), Error> {
+    let key = caller_account.key;
+    let caller = context
+        .invoke_stack
+        .last()
+        .ok_or("No program is executing")?;
+    let serialized = caller
+        .serialized_account(&key)
+        .ok_or_else(|| format!("MissingAccount: {}", key))?;
+    // The caller's account info must point into its input region
+    if caller_account.lamports_addr != serialized.lamports_addr
+        || caller_account.owner_addr != serialized.owner_key_addr
+        || caller_account.data_addr != serialized.data_addr
+    {
+        return Err(format!("Invalid account info pointer: {}", key).into());
+    }
+    if caller_account.data_len > (serialized.original_data_len + MAX_PERMITTED_DATA_INCREASE) as u64
+    {
+        return Err(format!("InvalidRealloc: account {}", key).into());
+    }
+    let is_writable = caller.is_writable(&key);
+    context.consume_checked(caller_account.data_len / CPI_BYTES_PER_UNIT)?;
+
+    let pre = context
+        .account(&key)
+        .ok_or_else(|| format!("MissingAccount: {}", key))?
+        .clone();
+    let post = Account {
+        lamports: read_u64(memory_mapping, caller_account.lamports_addr)?,
+        data: translate_slice(
+            memory_mapping,
+            caller_account.data_addr,
+            caller_account.data_len,
+        )?
+        .to_vec(),
+        owner: read_pubkey(memory_mapping, caller_account.owner_addr)?,
+        ..pre.clone()
+    };
+    account_rules::verify_account(&context.program_id, &pre, &post, is_writable)?;
+    context.update_accounts(&[post]);
+    Ok(())
+}
+
+/// Writes the callee's changes to an account back into the caller's memory,
+/// resizing the caller's data slice within the space reserved for growth.
+fn update_caller_account(
+    context: &SolanaContext,
+    memory_mapping: &MemoryMapping,
+    caller_account: &CallerAccount,
+) -> Result<(), Error> {
+    let key = caller_account.key;
+    let account = context
+        .account(&key)
+        .ok_or_else(|| format!("MissingAccount: {}", key))?;
+    let original_data_len = context
+        .invoke_stack
+        .last()
+        .and_then(|caller| caller.serialized_account(&key))
+        .ok_or_else(|| format!("MissingAccount: {}", key))?
+        .original_data_len;
+
+    write_u64(
+        memory_mapping,
+        caller_account.lamports_addr,
+        account.lamports,
+    )?;
+    translate_slice_mut(
+        memory_mapping,
+        caller_account.owner_addr,
+        size_of::<Pubkey>() as u64,
+    )?
+    .copy_from_slice(account.owner.as_ref());
+
+    let post_len = account.data.len() as u64;
+    if post_len != caller_account.data_len {
+        if post_len > (original_data_len + MAX_PERMITTED_DATA_INCREASE) as u64 {
+            return Err(format!("InvalidRealloc: account {}", key).into());
+        }
+        // Bytes released by shrinking must read as zero if the account grows again
+        if post_len < caller_account.data_len {
+            translate_slice_mut(
+                memory_mapping,
+                caller_account.data_addr.saturating_add(post_len),
+                caller_account.data_len - post_len,
+            )?
+            .fill(0);
+        }
+        write_u64(memory_mapping, caller_account.ref_to_len_addr, post_len)?;
+        // The serialized data length immediately follows the lamports
+        write_u64(
+            memory_mapping,
+            caller_account
+                .lamports_addr
+                .saturating_add(size_of::<u64>() as u64),
+            post_len,
+        )?;
+    }
+    translate_slice_mut(memory_mapping, caller_account.data_addr, post_len)?
+        .copy_from_slice(&account.data);
+    Ok(())
+}
+
+/// Checks the callee can be pushed onto the invoke stack. A program may call itself
+/// directly, but not be re-entered through another program.
+fn check_invoke_stack(invoke_stack: &[InvokeFrame], program_id: &Pubkey) -> Result<(), String> {
+    if invoke_stack.len() >= MAX_INVOKE_STACK_HEIGHT {
+        return Err(format!(
+            "CallDepth: invoke stack height {}",
+            MAX_INVOKE_STACK_HEIGHT
+        ));
+    }
+    let on_stack = invoke_stack
+        .iter()
+        .any(|frame| frame.program_id == *program_id);
+    let is_caller = invoke_stack
+        .last()
+        .is_some_and(|frame| frame.program_id == *program_id);
+    if on_stack && !is_caller {
+        return Err(format!("ReentrancyNotAllowed: {}", program_id));
+    }
+    Ok(())
+}
+
+/// Checks the callee's program account was passed to the caller and is executable,
+/// so a program can only invoke programs it was given.
+fn check_program_account(context: &SolanaContext, program_id: &Pubkey) -> Result<(), String> {
+    let caller = context
+        .invoke_stack
+        .last()
+        .ok_or("No program is executing")?;
+    if !caller.contains(program_id) {
+        return Err(format!("MissingAccount: program {}", program_id));
+    }
+    match context.account(program_id) {
+        Some(account) if account.executable => Ok(()),
+        _ => Err(format!("AccountNotExecutable: {}", program_id)),
+    }
+}
+
+/// Shared body of both invoke syscalls, once the caller's structures are translated.
+fn cpi_common(
+    context: &mut SolanaContext,
+    memory_mapping: &MemoryMapping,
+    instruction: Instruction,
+    caller_accounts: Vec<CallerAccount>,
+    signers: Vec<Pubkey>,
+) -> Result<u64, Error> {
+    context.consume_checked(
+        INVOKE_UNITS.saturating_add(instruction.data.len() as u64 / CPI_BYTES_PER_UNIT),
+    )?;
+
+    let program_id = instruction.program_id;
+    check_invoke_stack(&context.invoke_stack, &program_id)?;
+
+    // Repeated accounts take the union of their privileges, which the caller must hold
+    let caller = context
+        .invoke_stack
+        .last()
+        .ok_or("No program is executing")?;
+    let mut metas: Vec<AccountMeta> = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        if !caller.contains(&meta.pubkey) {
+            return Err(format!("MissingAccount: {}", meta.pubkey).into());
+        }
+        let is_signer = instruction
+            .accounts
+            .iter()
+            .any(|other| other.pubkey == meta.pubkey && other.is_signer);
+        let is_writable = instruction
+            .accounts
+            .iter()
+            .any(|other| other.pubkey == meta.pubkey && other.is_writable);
+        if is_writable && !caller.is_writable(&meta.pubkey) {
+            return Err(format!("PrivilegeEscalation: {} is not writable", meta.pubkey).into());
+        }
+        if is_signer && !caller.is_signer(&meta.pubkey) && !signers.contains(&meta.pubkey) {
+            return Err(format!("PrivilegeEscalation: {} did not sign", meta.pubkey).into());
+        }
+        metas.push(AccountMeta {
+            pubkey: meta.pubkey,
+            is_signer,
+            is_writable,
+        });
+    }
+
+    check_program_account(context, &program_id)?;
+
+    // Every account in the instruction needs an account info to sync through
+    let mut synced_accounts: Vec<&CallerAccount> = Vec::new();
+    for meta in &metas {
+        if synced_accounts
+            .iter()
+            .any(|synced| synced.key == meta.pubkey)
+        {
+            continue;
+        }
+        let caller_account = caller_accounts
+            .iter()
+            .find(|caller_account| caller_account.key == meta.pubkey)
+            .ok_or_else(|| format!("MissingAccount: no account info for {}", meta.pubkey))?;
+        sync_caller_account(context, memory_mapping, caller_account)?;
+        synced_accounts.push(caller_account);
+    }
+
+    let callee_accounts = metas
+        .iter()
+        .map(|meta| -> Result<Account, Error> {
+            let state = context
+                .account(&meta.pubkey)
+                .ok_or_else(|| format!("MissingAccount: {}", meta.pubkey))?;
+            Ok(Account {
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+                ..state.clone()
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let result = if program_id == system_program::id() {
+        invoke::execute_system_program(context, callee_accounts, &instruction.data).0
+    } else {
+        let executable = context.load_program(&program_id)?;
+        invoke::execute_program(
+            context,
+            &executable,
+            program_id,
+            callee_accounts,
+            &instruction.data,
+        )
+        .1
+    };
+    if !result.is_success() {
+        return Err(format!("Invoked program {} failed: {}", program_id, result).into());
+    }
+
+    for caller_account in synced_accounts {
+        if metas
+            .iter()
+            .any(|meta| meta.pubkey == caller_account.key && meta.is_writable)
+        {
+            update_caller_account(context, memory_mapping, caller_account)?;
+        }
+    }
+    Ok(0)
+}
+
+// Implements sol_invoke_signed_rust, reading the instruction and account infos
+// as laid out by the Rust SDK.
+declare_builtin_function!(
+    SyscallInvokeSignedRust,
+    fn rust(
+        context: &mut SolanaContext,
+        instruction_addr: u64,
+        account_infos_addr: u64,
+        account_infos_len: u64,
+        signers_seeds_addr: u64,
+        signers_seeds_len: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let instruction = translate_instruction_rust(memory_mapping, instruction_addr)?;
+        let caller_accounts =
+            translate_account_infos_rust(memory_mapping, account_infos_addr, account_infos_len)?;
+        let signers = translate_signers(
+            memory_mapping,
+            signers_seeds_addr,
+            signers_seeds_len,
+            &context.program_id,
+        )?;
+        cpi_common(
+            context,
+            memory_mapping,
+            instruction,
+            caller_accounts,
+            signers,
+        )
+    }
+);
+
+// Implements sol_invoke_signed_c, reading the instruction and account infos
+// as laid out by the C SDK.
+declare_builtin_function!(
+    SyscallInvokeSignedC,
+    fn rust(
+        context: &mut SolanaContext,
+        instruction_addr: u64,
+        account_infos_addr: u64,
+        account_infos_len: u64,
+        signers_seeds_addr: u64,
+        signers_seeds_len: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let instruction = translate_instruction_c(memory_mapping, instruction_addr)?;
+        let caller_accounts =
+            translate_account_infos_c(memory_mapping, account_infos_addr, account_infos_len)?;
+        let signers = translate_signers(
+            memory_mapping,
+            signers_seeds_addr,
+            signers_seeds_len,
+            &context.program_id,
+        )?;
+        cpi_common(
+            context,
+            memory_mapping,
+            instruction,
+            caller_accounts,
+            signers,
+        )
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serializer::{SerializedAccount, VmSerializedAccount};
+    use solana_sbpf::{memory_region::MemoryRegion, program::SBPFVersion, vm::Config};
+
+    /// Where test memory is mapped into the VM.
+    const VM_ADDR: u64 = 0x1_0000_0000;
+
+    /// Program memory laid out by a test, addressed by VM address.
+    struct Memory(Vec<u8>);
+
+    impl Memory {
+        fn new() -> Self {
+            Self(vec![0; 0x4000])
+        }
+
+        fn write(&mut self, addr: u64, bytes: &[u8]) {
+            let offset = (addr - VM_ADDR) as usize;
+            self.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+
+        fn write_u64(&mut self, addr: u64, value: u64) {
+            self.write(addr, &value.to_le_bytes());
+        }
+
+        fn read(&self, addr: u64, len: usize) -> &[u8] {
+            let offset = (addr - VM_ADDR) as usize;
+            &self.0[offset..offset + len]
+        }
+
+        fn read_u64(&self, addr: u64) -> u64 {
+            u64_at(self.read(addr, size_of::<u64>()), 0)
+        }
+
+        /// Runs `f` with the memory mapped at `VM_ADDR`.
+        fn map<T>(&mut self, f: impl FnOnce(&MemoryMapping) -> T) -> T {
+            let config = Config::default();
+            let memory_mapping = MemoryMapping::new(
+                vec![MemoryRegion::new_writable(&mut self.0, VM_ADDR)],
+                &config,
+                SBPFVersion::V3,
+            )
+            .unwrap();
+            f(&memory_mapping)
+        }
+    }
+
+    fn frame(program_id: Pubkey) -> InvokeFrame {
+        InvokeFrame {
+            program_id,
+            accounts: vec![],
+            serialized_accounts: vec![],
+        }
+    }
+
+    #[test]
+    fn direct_recursion() {
+        let program_id = Pubkey::from([1; 32]);
+        let mut invoke_stack = vec![frame(program_id)];
+        // A -> A -> A -> A
+        for _ in 0..3 {
+            assert_eq!(check_invoke_stack(&invoke_stack, &program_id), Ok(()));
+            invoke_stack.push(frame(program_id));
+        }
+        assert_eq!(invoke_stack.len(), 4);
+    }
+
+    #[test]
+    fn reentrancy() {
+        let a = Pubkey::from([1; 32]);
+        let b = Pubkey::from([2; 32]);
+        let invoke_stack = vec![frame(a), frame(b)];
+        assert_eq!(check_invoke_stack(&invoke_stack, &b), Ok(()));
+        assert!(check_invoke_stack(&invoke_stack, &a)
+            .unwrap_err()
+            .starts_with("ReentrancyNotAllowed"));
+    }
+
+    #[test]
+    fn program_account() {
+        let caller = Pubkey::from([1; 32]);
+        let callee = Pubkey::from([2; 32]);
+        let program_account = |executable| Account {
+            pubkey: callee,
+            is_signer: false,
+            is_writable: false,
+            lamports: 1,
+            data: vec![],
+            owner: Pubkey::from([3; 32]),
+            executable,
+            rent_epoch: 0,
+        };
+        let mut context = SolanaContext {
+            transaction_accounts: vec![program_account(true)],
+            invoke_stack: vec![frame(caller)],
+            ..SolanaContext::default()
+        };
+
+        // The callee is loaded but was not passed to the caller
+        assert!(check_program_account(&context, &callee)
+            .unwrap_err()
+            .starts_with("MissingAccount"));
+
+        context.invoke_stack[0].accounts = vec![program_account(true)];
+        assert_eq!(check_program_account(&context, &callee), Ok(()));
+
+        context.transaction_accounts = vec![program_account(false)];
+        assert!(check_program_account(&context, &callee)
+            .unwrap_err()
+            .starts_with("AccountNotExecutable"));
+    }
+
+    #[test]
+    fn call_depth() {
+        let program_id = Pubkey::from([1; 32]);
+        let invoke_stack: Vec<InvokeFrame> = (0..MAX_INVOKE_STACK_HEIGHT)
+            .map(|_| frame(program_id))
+            .collect();
+        assert!(check_invoke_stack(&invoke_stack, &program_id)
+            .unwrap_err()
+            .starts_with("CallDepth"));
+    }
+
+    #[test]
+    fn translate_rust_structs() {
+        let program_id = Pubkey::from([1; 32]);
+        let key = Pubkey::from([2; 32]);
+        let (instruction, metas, data, info) =
+            (VM_ADDR, VM_ADDR + 0x100, VM_ADDR + 0x200, VM_ADDR + 0x300);
+        let (key_addr, owner, lamports_rc, data_rc) = (
+            VM_ADDR + 0x400,
+            VM_ADDR + 0x420,
+            VM_ADDR + 0x500,
+            VM_ADDR + 0x540,
+        );
+        let (lamports, account_data) = (VM_ADDR + 0x600, VM_ADDR + 0x700);
+
+        let mut memory = Memory::new();
+        // StableInstruction: accounts and data as (ptr, capacity, len), then the program id
+        memory.write_u64(instruction, metas);
+        memory.write_u64(instruction + 8, 2);
+        memory.write_u64(instruction + 16, 2);
+        memory.write_u64(instruction + 24, data);
+        memory.write_u64(instruction + 32, 3);
+        memory.write_u64(instruction + 40, 3);
+        memory.write(instruction + 48, program_id.as_ref());
+        // AccountMeta: pubkey, is_signer, is_writable
+        memory.write(metas, key.as_ref());
+        memory.write(metas + 32, &[1, 0]);
+        memory.write(metas + RUST_ACCOUNT_META_SIZE, key.as_ref());
+        memory.write(metas + RUST_ACCOUNT_META_SIZE + 32, &[0, 1]);
+        memory.write(data, &[7, 8, 9]);
+        // AccountInfo: key, lamports and data Rc<RefCell>s, then the owner
+        memory.write_u64(info, key_addr);
+        memory.write_u64(info + 8, lamports_rc);
+        memory.write_u64(info + 16, data_rc);
+        memory.write_u64(info + 24, owner);
+        memory.write(key_addr, key.as_ref());
+        memory.write_u64(lamports_rc + RC_REF_CELL_VALUE_OFFSET, lamports);
+        memory.write_u64(data_rc + RC_REF_CELL_VALUE_OFFSET, account_data);
+        memory.write_u64(data_rc + RC_REF_CELL_VALUE_OFFSET + 8, 5);
+
+        let (instruction, caller_accounts) = memory.map(|memory_mapping| {
+            (
+                translate_instruction_rust(memory_mapping, instruction).unwrap(),
+                translate_account_infos_rust(memory_mapping, info, 1).unwrap(),
+            )
+        });
+        assert_eq!(instruction.program_id, program_id);
+        assert_eq!(instruction.data, [7, 8, 9]);
+        let flags: Vec<_> = instruction
+            .accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+            .collect();
+        assert_eq!(flags, [(key, true, false), (key, false, true)]);
+
+        let caller_account = &caller_accounts[0];
+        assert_eq!(caller_account.key, key);
+        assert_eq!(caller_account.lamports_addr, lamports);
+        assert_eq!(caller_account.owner_addr, owner);
+        assert_eq!(caller_account.data_addr, account_data);
+        assert_eq!(caller_account.data_len, 5);
+        assert_eq!(
+            caller_account.ref_to_len_addr,
+            data_rc + RC_REF_CELL_VALUE_OFFSET + 8
+        );
+    }
+
+    #[test]
+    fn translate_c_structs() {
+        let program_id = Pubkey::from([1; 32]);
+        let key = Pubkey::from([2; 32]);
+        let (instruction, metas, data, infos) =
+            (VM_ADDR, VM_ADDR + 0x100, VM_ADDR + 0x200, VM_ADDR + 0x300);
+        let (program_id_addr, key_addr, owner, lamports, account_data) = (
+            VM_ADDR + 0x400,
+            VM_ADDR + 0x420,
+            VM_ADDR + 0x440,
+            VM_ADDR + 0x500,
+            VM_ADDR + 0x600,
+        );
+
+        let mut memory = Memory::new();
+        // SolInstruction: program id pointer, then accounts and data as (ptr, len)
+        memory.write_u64(instruction, program_id_addr);
+        memory.write_u64(instruction + 8, metas);
+        memory.write_u64(instruction + 16, 1);
+        memory.write_u64(instruction + 24, data);
+        memory.write_u64(instruction + 32, 2);
+        memory.write(program_id_addr, program_id.as_ref());
+        // SolAccountMeta: pubkey pointer, is_writable, is_signer
+        memory.write_u64(metas, key_addr);
+        memory.write(metas + 8, &[1, 0]);
+        memory.write(data, &[7, 8]);
+        // The second SolAccountInfo: key, lamports, data_len, data, owner
+        let info = infos + C_ACCOUNT_INFO_SIZE;
+        memory.write_u64(infos, key_addr);
+        memory.write_u64(info, key_addr);
+        memory.write_u64(info + 8, lamports);
+        memory.write_u64(info + 16, 4);
+        memory.write_u64(info + 24, account_data);
+        memory.write_u64(info + 32, owner);
+        memory.write(key_addr, key.as_ref());
+
+        let (instruction, caller_accounts) = memory.map(|memory_mapping| {
+            (
+                translate_instruction_c(memory_mapping, instruction).unwrap(),
+                translate_account_infos_c(memory_mapping, infos, 2).unwrap(),
+            )
+        });
+        assert_eq!(instruction.program_id, program_id);
+        assert_eq!(instruction.data, [7, 8]);
+        let meta = &instruction.accounts[0];
+        assert_eq!(
+            (meta.pubkey, meta.is_signer, meta.is_writable),
+            (key, false, true)
+        );
+
+        let caller_account = &caller_accounts[1];
+        assert_eq!(caller_account.key, key);
+        assert_eq!(caller_account.lamports_addr, lamports);
+        assert_eq!(caller_account.owner_addr, owner);
+        assert_eq!(caller_account.data_addr, account_data);
+        assert_eq!(caller_account.data_len, 4);
+        assert_eq!(
+            caller_account.ref_to_len_addr,
+            info + C_ACCOUNT_INFO_DATA_LEN_OFFSET
+        );
+    }
+
+    #[test]
+    fn translate_pda_signers() {
+        let program_id = Pubkey::from([1; 32]);
+        let (address, bump) = Pubkey::find_program_address(&[b"vault", &[3; 4]], &program_id);
+        let (signers, seeds, seed_bytes) = (VM_ADDR, VM_ADDR + 0x100, VM_ADDR + 0x200);
+
+        let mut memory = Memory::new();
+        // One signer of three seeds, each an (addr, len) slice
+        memory.write_u64(signers, seeds);
+        memory.write_u64(signers + 8, 3);
+        for (index, seed) in [&b"vault"[..], &[3; 4], &[bump]].iter().enumerate() {
+            let seed_addr = seed_bytes + index as u64 * 0x20;
+            memory.write(seed_addr, seed);
+            memory.write_u64(seeds + index as u64 * 16, seed_addr);
+            memory.write_u64(seeds + index as u64 * 16 + 8, seed.len() as u64);
+        }
+
+        let signers = memory.map(|memory_mapping| {
+            translate_signers(memory_mapping, signers, 1, &program_id).unwrap()
+        });
+        assert_eq!(signers, [address]);
+
+        // The same seeds sign for nothing under another program
+        let other_program = Pubkey::from([2; 32]);
+        let signers = memory
+            .map(|memory_mapping| translate_signers(memory_mapping, VM_ADDR, 1, &other_program));
+        assert!(signers.map_or(true, |signers| signers != [address]));
+
+        let too_many = memory.map(|memory_mapping| {
+            translate_signers(memory_mapping, VM_ADDR, MAX_SIGNERS + 1, &program_id)
+        });
+        assert!(too_many.is_err());
+    }
+
+    /// A caller holding one account with `original_len` bytes of data, serialized at
+    /// the addresses its account info points to.
+    fn realloc_fixture(original_len: usize) -> (SolanaContext, CallerAccount, Memory) {
+        let key = Pubkey::from([2; 32]);
+        let (lamports, owner, ref_to_len, data) =
+            (VM_ADDR, VM_ADDR + 0x10, VM_ADDR + 0x30, VM_ADDR + 0x40);
+        let account = Account {
+            pubkey: key,
+            is_signer: false,
+            is_writable: true,
+            lamports: 10,
+            data: vec![0xaa; original_len],
+            owner: Pubkey::from([1; 32]),
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let mut memory = Memory::new();
+        // The serialized data length follows the lamports, as in the input region
+        memory.write_u64(lamports, account.lamports);
+        memory.write_u64(lamports + 8, original_len as u64);
+        memory.write(owner, account.owner.as_ref());
+        memory.write_u64(ref_to_len, original_len as u64);
+        memory.write(data, &account.data);
+
+        let context = SolanaContext {
+            transaction_accounts: vec![account.clone()],
+            invoke_stack: vec![InvokeFrame {
+                program_id: account.owner,
+                accounts: vec![account],
+                serialized_accounts: vec![SerializedAccount::Account(VmSerializedAccount {
+                    public_key_addr: 0,
+                    owner_key_addr: owner,
+                    lamports_addr: lamports,
+                    data_addr: data,
+                    original_data_len: original_len,
+                })],
+            }],
+            ..SolanaContext::default()
+        };
+        let caller_account = CallerAccount {
+            key,
+            lamports_addr: lamports,
+            owner_addr: owner,
+            data_addr: data,
+            data_len: original_len as u64,
+            ref_to_len_addr: ref_to_len,
+        };
+        (context, caller_account, memory)
+    }
+
+    #[test]
+    fn update_caller_account_grow() {
+        let (mut context, caller_account, mut memory) = realloc_fixture(4);
+        context.transaction_accounts[0].lamports = 20;
+        context.transaction_accounts[0].data = vec![0xbb; 14];
+
+        memory
+            .map(|memory_mapping| update_caller_account(&context, memory_mapping, &caller_account))
+            .unwrap();
+        assert_eq!(memory.read_u64(caller_account.lamports_addr), 20);
+        assert_eq!(memory.read_u64(caller_account.lamports_addr + 8), 14);
+        assert_eq!(memory.read_u64(caller_account.ref_to_len_addr), 14);
+        assert_eq!(memory.read(caller_account.data_addr, 14), [0xbb; 14]);
+    }
+
+    #[test]
+    fn update_caller_account_shrink() {
+        let (mut context, caller_account, mut memory) = realloc_fixture(4);
+        context.transaction_accounts[0].data = vec![0xbb; 2];
+
+        memory
+            .map(|memory_mapping| update_caller_account(&context, memory_mapping, &caller_account))
+            .unwrap();
+        assert_eq!(memory.read_u64(caller_account.lamports_addr + 8), 2);
+        assert_eq!(memory.read_u64(caller_account.ref_to_len_addr), 2);
+        // The released tail reads as zero
+        assert_eq!(memory.read(caller_account.data_addr, 4), [0xbb, 0xbb, 0, 0]);
+    }
+
+    #[test]
+    fn update_caller_account_realloc_limit() {
+        let (mut context, caller_account, mut memory) = realloc_fixture(4);
+        context.transaction_accounts[0].data = vec![0xbb; 4 + MAX_PERMITTED_DATA_INCREASE + 1];
+
+        let result = memory
+            .map(|memory_mapping| update_caller_account(&context, memory_mapping, &caller_account));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("InvalidRealloc"));
+        // The caller's lengths are left alone
+        assert_eq!(memory.read_u64(caller_account.ref_to_len_addr), 4);
+
+        context.transaction_accounts[0].data = vec![0xbb; 4 + MAX_PERMITTED_DATA_INCREASE];
+        memory
+            .map(|memory_mapping| update_caller_account(&context, memory_mapping, &caller_account))
+            .unwrap();
+        assert_eq!(
+            memory.read_u64(caller_account.ref_to_len_addr),
+            (4 + MAX_PERMITTED_DATA_INCREASE) as u64
+        );
+    }
+}
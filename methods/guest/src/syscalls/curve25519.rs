@@ -0,0 +1,386 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! curve25519 syscalls over the Edwards and Ristretto representations, as used by
+//! the zk-token proof program and token-2022 confidential transfers.
+//! Points are 32-byte compressed encodings and scalars must be canonical; every
+//! failure is reported to the program as a return value of 1, as on chain.
+
+use super::{translate_slice, translate_slice_mut};
+use crate::{
+    compute_budget::{
+        CURVE25519_EDWARDS_ADD_COST, CURVE25519_EDWARDS_MSM_BASE_COST,
+        CURVE25519_EDWARDS_MSM_INCREMENTAL_COST, CURVE25519_EDWARDS_MULTIPLY_COST,
+        CURVE25519_EDWARDS_SUBTRACT_COST, CURVE25519_EDWARDS_VALIDATE_POINT_COST,
+        CURVE25519_RISTRETTO_ADD_COST, CURVE25519_RISTRETTO_MSM_BASE_COST,
+        CURVE25519_RISTRETTO_MSM_INCREMENTAL_COST, CURVE25519_RISTRETTO_MULTIPLY_COST,
+        CURVE25519_RISTRETTO_SUBTRACT_COST, CURVE25519_RISTRETTO_VALIDATE_POINT_COST,
+    },
+    SolanaContext,
+};
+use curve25519_dalek::{
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::VartimeMultiscalarMul,
+};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+// Curves accepted by the curve syscalls
+pub const CURVE25519_EDWARDS: u64 = 0;
+pub const CURVE25519_RISTRETTO: u64 = 1;
+
+// Operations accepted by sol_curve_group_op
+pub const ADD: u64 = 0;
+pub const SUB: u64 = 1;
+pub const MUL: u64 = 2;
+
+/// Maximum number of points sol_curve_multiscalar_mul accepts.
+pub const MAX_MULTISCALAR_POINTS: u64 = 512;
+
+const POINT_SIZE: usize = 32;
+const SCALAR_SIZE: usize = 32;
+
+/// Returned to the program for invalid points, scalars, curves or operations.
+const FAILURE: u64 = 1;
+
+/// Point representation the curve syscalls operate on.
+trait CurvePoint: Sized {
+    fn decompress(bytes: &[u8]) -> Option<Self>;
+    fn compress(&self) -> [u8; POINT_SIZE];
+    fn add(&self, other: &Self) -> Self;
+    fn subtract(&self, other: &Self) -> Self;
+    fn multiply(&self, scalar: &Scalar) -> Self;
+    fn multiscalar_multiply(scalars: Vec<Scalar>, points: Vec<Self>) -> Self;
+}
+
+impl CurvePoint for EdwardsPoint {
+    fn decompress(bytes: &[u8]) -> Option<Self> {
+        CompressedEdwardsY::from_slice(bytes).ok()?.decompress()
+    }
+
+    fn compress(&self) -> [u8; POINT_SIZE] {
+        EdwardsPoint::compress(self).to_bytes()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn subtract(&self, other: &Self) -> Self {
+        self - other
+    }
+
+    fn multiply(&self, scalar: &Scalar) -> Self {
+        scalar * self
+    }
+
+    fn multiscalar_multiply(scalars: Vec<Scalar>, points: Vec<Self>) -> Self {
+        EdwardsPoint::vartime_multiscalar_mul(scalars, points)
+    }
+}
+
+impl CurvePoint for RistrettoPoint {
+    fn decompress(bytes: &[u8]) -> Option<Self> {
+        CompressedRistretto::from_slice(bytes).ok()?.decompress()
+    }
+
+    fn compress(&self) -> [u8; POINT_SIZE] {
+        RistrettoPoint::compress(self).to_bytes()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn subtract(&self, other: &Self) -> Self {
+        self - other
+    }
+
+    fn multiply(&self, scalar: &Scalar) -> Self {
+        scalar * self
+    }
+
+    fn multiscalar_multiply(scalars: Vec<Scalar>, points: Vec<Self>) -> Self {
+        RistrettoPoint::vartime_multiscalar_mul(scalars, points)
+    }
+}
+
+/// Parses a scalar, rejecting encodings that are not reduced modulo the group order.
+fn scalar(bytes: &[u8]) -> Option<Scalar> {
+    Scalar::from_canonical_bytes(bytes.try_into().ok()?).into()
+}
+
+/// Applies a group operation. For `MUL` the left input is the scalar and the
+/// right input the point, matching Solana's argument order.
+fn group_op<P: CurvePoint>(op: u64, left: &[u8], right: &[u8]) -> Option<[u8; POINT_SIZE]> {
+    let result = match op {
+        ADD => P::decompress(left)?.add(&P::decompress(right)?),
+        SUB => P::decompress(left)?.subtract(&P::decompress(right)?),
+        MUL => P::decompress(right)?.multiply(&scalar(left)?),
+        _ => return None,
+    };
+    Some(result.compress())
+}
+
+/// Computes the sum of `scalars[i] * points[i]` over packed 32-byte encodings.
+fn multiscalar_mul<P: CurvePoint>(scalars: &[u8], points: &[u8]) -> Option<[u8; POINT_SIZE]> {
+    let scalars = (0..scalars.len() / SCALAR_SIZE)
+        .map(|index| scalar(&scalars[index * SCALAR_SIZE..][..SCALAR_SIZE]))
+        .collect::<Option<Vec<Scalar>>>()?;
+    let points = (0..points.len() / POINT_SIZE)
+        .map(|index| P::decompress(&points[index * POINT_SIZE..][..POINT_SIZE]))
+        .collect::<Option<Vec<P>>>()?;
+    Some(P::multiscalar_multiply(scalars, points).compress())
+}
+
+/// Returns 0 if the bytes decode to a point on the curve, or `FAILURE`.
+fn validate_point(curve_id: u64, point: &[u8]) -> u64 {
+    let valid = match curve_id {
+        CURVE25519_EDWARDS => EdwardsPoint::decompress(point).is_some(),
+        CURVE25519_RISTRETTO => RistrettoPoint::decompress(point).is_some(),
+        _ => false,
+    };
+    if valid {
+        0
+    } else {
+        FAILURE
+    }
+}
+
+/// Applies a group operation on the given curve.
+fn curve_group_op(curve_id: u64, op: u64, left: &[u8], right: &[u8]) -> Option<[u8; POINT_SIZE]> {
+    match curve_id {
+        CURVE25519_EDWARDS => group_op::<EdwardsPoint>(op, left, right),
+        CURVE25519_RISTRETTO => group_op::<RistrettoPoint>(op, left, right),
+        _ => None,
+    }
+}
+
+/// Computes a multiscalar multiplication on the given curve.
+fn curve_multiscalar_mul(curve_id: u64, scalars: &[u8], points: &[u8]) -> Option<[u8; POINT_SIZE]> {
+    match curve_id {
+        CURVE25519_EDWARDS => multiscalar_mul::<EdwardsPoint>(scalars, points),
+        CURVE25519_RISTRETTO => multiscalar_mul::<RistrettoPoint>(scalars, points),
+        _ => None,
+    }
+}
+
+// Implements sol_curve_validate_point: returns 0 if the bytes decode to a point
+// on the requested curve, 1 otherwise.
+declare_builtin_function!(
+    SyscallCurvePointValidation,
+    fn rust(
+        context: &mut SolanaContext,
+        curve_id: u64,
+        point_addr: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let cost = match curve_id {
+            CURVE25519_EDWARDS => CURVE25519_EDWARDS_VALIDATE_POINT_COST,
+            CURVE25519_RISTRETTO => CURVE25519_RISTRETTO_VALIDATE_POINT_COST,
+            _ => return Ok(FAILURE),
+        };
+        context.consume_checked(cost)?;
+
+        let point = translate_slice(memory_mapping, point_addr, POINT_SIZE as u64)?;
+        Ok(validate_point(curve_id, point))
+    }
+);
+
+// Implements sol_curve_group_op: adds, subtracts or multiplies points, writing the
+// compressed result and returning 0, or returning 1 for invalid input.
+declare_builtin_function!(
+    SyscallCurveGroupOps,
+    fn rust(
+        context: &mut SolanaContext,
+        curve_id: u64,
+        op: u64,
+        left_input_addr: u64,
+        right_input_addr: u64,
+        result_point_addr: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let cost = match (curve_id, op) {
+            (CURVE25519_EDWARDS, ADD) => CURVE25519_EDWARDS_ADD_COST,
+            (CURVE25519_EDWARDS, SUB) => CURVE25519_EDWARDS_SUBTRACT_COST,
+            (CURVE25519_EDWARDS, MUL) => CURVE25519_EDWARDS_MULTIPLY_COST,
+            (CURVE25519_RISTRETTO, ADD) => CURVE25519_RISTRETTO_ADD_COST,
+            (CURVE25519_RISTRETTO, SUB) => CURVE25519_RISTRETTO_SUBTRACT_COST,
+            (CURVE25519_RISTRETTO, MUL) => CURVE25519_RISTRETTO_MULTIPLY_COST,
+            _ => return Ok(FAILURE),
+        };
+        context.consume_checked(cost)?;
+
+        let left = translate_slice(memory_mapping, left_input_addr, POINT_SIZE as u64)?;
+        let right = translate_slice(memory_mapping, right_input_addr, POINT_SIZE as u64)?;
+        match curve_group_op(curve_id, op, left, right) {
+            Some(point) => {
+                translate_slice_mut(memory_mapping, result_point_addr, POINT_SIZE as u64)?
+                    .copy_from_slice(&point);
+                Ok(0)
+            }
+            None => Ok(FAILURE),
+        }
+    }
+);
+
+// Implements sol_curve_multiscalar_mul over `points_len` scalars and points.
+// More than 512 points aborts the program; invalid input returns 1.
+declare_builtin_function!(
+    SyscallCurveMultiscalarMultiplication,
+    fn rust(
+        context: &mut SolanaContext,
+        curve_id: u64,
+        scalars_addr: u64,
+        points_addr: u64,
+        points_len: u64,
+        result_point_addr: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        if points_len > MAX_MULTISCALAR_POINTS {
+            return Err(format!(
+                "Multiscalar multiplication of {} points exceeds the limit of {}",
+                points_len, MAX_MULTISCALAR_POINTS
+            )
+            .into());
+        }
+
+        let (base_cost, incremental_cost) = match curve_id {
+            CURVE25519_EDWARDS => (
+                CURVE25519_EDWARDS_MSM_BASE_COST,
+                CURVE25519_EDWARDS_MSM_INCREMENTAL_COST,
+            ),
+            CURVE25519_RISTRETTO => (
+                CURVE25519_RISTRETTO_MSM_BASE_COST,
+                CURVE25519_RISTRETTO_MSM_INCREMENTAL_COST,
+            ),
+            _ => return Ok(FAILURE),
+        };
+        context.consume_checked(
+            base_cost.saturating_add(incremental_cost.saturating_mul(points_len.saturating_sub(1))),
+        )?;
+
+        let scalars = translate_slice(
+            memory_mapping,
+            scalars_addr,
+            points_len.saturating_mul(SCALAR_SIZE as u64),
+        )?;
+        let points = translate_slice(
+            memory_mapping,
+            points_addr,
+            points_len.saturating_mul(POINT_SIZE as u64),
+        )?;
+        match curve_multiscalar_mul(curve_id, scalars, points) {
+            Some(point) => {
+                translate_slice_mut(memory_mapping, result_point_addr, POINT_SIZE as u64)?
+                    .copy_from_slice(&point);
+                Ok(0)
+            }
+            None => Ok(FAILURE),
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Compressed multiples of each curve's base point
+    const EDWARDS_B: &str = "5866666666666666666666666666666666666666666666666666666666666666";
+    const EDWARDS_2B: &str = "c9a3f86aae465f0e56513864510f3997561fa2c9e85ea21dc2292309f3cd6022";
+    const EDWARDS_3B: &str = "d4b4f5784868c3020403246717ec169ff79e26608ea126a1ab69ee77d1b16712";
+    const RISTRETTO_B: &str = "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76";
+    const RISTRETTO_2B: &str = "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919";
+    const RISTRETTO_3B: &str = "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259";
+
+    /// Bytes that decode to neither an Edwards nor a Ristretto point.
+    const INVALID_POINT: [u8; 32] = [
+        120, 140, 152, 233, 41, 227, 203, 27, 87, 115, 25, 251, 219, 5, 84, 148, 117, 38, 84, 60,
+        87, 144, 161, 146, 42, 34, 91, 155, 158, 189, 121, 79,
+    ];
+    const INVALID_CURVE: u64 = 2;
+
+    fn point(encoded: &str) -> Vec<u8> {
+        hex::decode(encoded).unwrap()
+    }
+
+    fn scalar_bytes(value: u8) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        bytes[0] = value;
+        bytes
+    }
+
+    #[test]
+    fn validate_points() {
+        assert_eq!(validate_point(CURVE25519_EDWARDS, &point(EDWARDS_B)), 0);
+        assert_eq!(validate_point(CURVE25519_RISTRETTO, &point(RISTRETTO_B)), 0);
+        assert_eq!(validate_point(CURVE25519_EDWARDS, &INVALID_POINT), FAILURE);
+        assert_eq!(
+            validate_point(CURVE25519_RISTRETTO, &INVALID_POINT),
+            FAILURE
+        );
+        assert_eq!(validate_point(INVALID_CURVE, &point(EDWARDS_B)), FAILURE);
+    }
+
+    #[test]
+    fn group_ops() {
+        for (curve_id, b, b2, b3) in [
+            (CURVE25519_EDWARDS, EDWARDS_B, EDWARDS_2B, EDWARDS_3B),
+            (
+                CURVE25519_RISTRETTO,
+                RISTRETTO_B,
+                RISTRETTO_2B,
+                RISTRETTO_3B,
+            ),
+        ] {
+            let (b, b2, b3) = (point(b), point(b2), point(b3));
+            assert_eq!(curve_group_op(curve_id, ADD, &b2, &b).unwrap().to_vec(), b3);
+            assert_eq!(curve_group_op(curve_id, SUB, &b3, &b).unwrap().to_vec(), b2);
+            assert_eq!(
+                curve_group_op(curve_id, MUL, &scalar_bytes(3), &b)
+                    .unwrap()
+                    .to_vec(),
+                b3
+            );
+
+            assert_eq!(curve_group_op(curve_id, ADD, &b, &INVALID_POINT), None);
+            // Scalars must be reduced modulo the group order
+            assert_eq!(curve_group_op(curve_id, MUL, &[0xff; 32], &b), None);
+            assert_eq!(curve_group_op(curve_id, MUL + 1, &b, &b), None);
+        }
+        let b = point(EDWARDS_B);
+        assert_eq!(curve_group_op(INVALID_CURVE, ADD, &b, &b), None);
+    }
+
+    #[test]
+    fn multiscalar_muls() {
+        let scalars = [scalar_bytes(2), scalar_bytes(1)].concat();
+        for (curve_id, b, b3) in [
+            (CURVE25519_EDWARDS, EDWARDS_B, EDWARDS_3B),
+            (CURVE25519_RISTRETTO, RISTRETTO_B, RISTRETTO_3B),
+        ] {
+            let points = [point(b), point(b)].concat();
+            assert_eq!(
+                curve_multiscalar_mul(curve_id, &scalars, &points)
+                    .unwrap()
+                    .to_vec(),
+                point(b3)
+            );
+
+            let invalid_points = [point(b), INVALID_POINT.to_vec()].concat();
+            assert_eq!(
+                curve_multiscalar_mul(curve_id, &scalars, &invalid_points),
+                None
+            );
+        }
+        let points = [point(EDWARDS_B), point(EDWARDS_B)].concat();
+        assert_eq!(
+            curve_multiscalar_mul(INVALID_CURVE, &scalars, &points),
+            None
+        );
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use super::{translate_slice_mut, translate_slices, Error};
+use crate::{
+    compute_budget::{MEM_OP_BASE_COST, SHA256_BASE_COST, SHA256_BYTE_COST, SHA256_MAX_SLICES},
+    SolanaContext,
+};
+use risc0_zkvm::sha::{Impl, Sha256};
+use sha3::{Digest, Keccak256};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+/// Size of the digest produced by every hashing syscall.
+const HASH_BYTES: u64 = 32;
+
+/// Shared body of the hashing syscalls.
+/// Charges Solana's cost model for each slice and writes the 32-byte digest to `result_addr`.
+fn hash_slices(
+    context: &mut SolanaContext,
+    name: &str,
+    vals_addr: u64,
+    vals_len: u64,
+    result_addr: u64,
+    memory_mapping: &MemoryMapping,
+    hash: fn(&[&[u8]]) -> [u8; 32],
+) -> Result<u64, Error> {
+    if vals_len > SHA256_MAX_SLICES {
+        context.log_collector.log(&format!(
+            "{} Hashing {} sequences in one syscall is over the limit {}",
+            name, vals_len, SHA256_MAX_SLICES
+        ));
+        return Err("Hashing too many sequences".into());
+    }
+    context.consume_checked(SHA256_BASE_COST)?;
+
+    let hash_result = translate_slice_mut(memory_mapping, result_addr, HASH_BYTES)?;
+    let vals = translate_slices(memory_mapping, vals_addr, vals_len)?;
+    for val in &vals {
+        context.consume_checked(
+            MEM_OP_BASE_COST.max(SHA256_BYTE_COST.saturating_mul(val.len() as u64 / 2)),
+        )?;
+    }
+
+    hash_result.copy_from_slice(&hash(&vals));
+    Ok(0)
+}
+
+/// SHA-256 of the concatenated slices, computed by the zkVM's accelerator.
+fn sha256(vals: &[&[u8]]) -> [u8; 32] {
+    Impl::hash_bytes(&vals.concat())
+        .as_bytes()
+        .try_into()
+        .expect("SHA-256 digest is 32 bytes")
+}
+
+/// Keccak-256 of the concatenated slices.
+fn keccak256(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for val in vals {
+        hasher.update(val);
+    }
+    hasher.finalize().into()
+}
+
+/// BLAKE3 of the concatenated slices.
+fn blake3(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    for val in vals {
+        hasher.update(val);
+    }
+    hasher.finalize().into()
+}
+
+// Implements sol_sha256 using the zkVM's SHA-256 accelerator.
+declare_builtin_function!(
+    SyscallSha256,
+    fn rust(
+        context: &mut SolanaContext,
+        vals_addr: u64,
+        vals_len: u64,
+        result_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        hash_slices(
+            context,
+            "Sha256",
+            vals_addr,
+            vals_len,
+            result_addr,
+            memory_mapping,
+            sha256,
+        )
+    }
+);
+
+// Implements sol_keccak256 (the original Keccak padding used by Ethereum).
+declare_builtin_function!(
+    SyscallKeccak256,
+    fn rust(
+        context: &mut SolanaContext,
+        vals_addr: u64,
+        vals_len: u64,
+        result_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        hash_slices(
+            context,
+            "Keccak256",
+            vals_addr,
+            vals_len,
+            result_addr,
+            memory_mapping,
+            keccak256,
+        )
+    }
+);
+
+// Implements sol_blake3.
+declare_builtin_function!(
+    SyscallBlake3,
+    fn rust(
+        context: &mut SolanaContext,
+        vals_addr: u64,
+        vals_len: u64,
+        result_addr: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        hash_slices(
+            context,
+            "Blake3",
+            vals_addr,
+            vals_len,
+            result_addr,
+            memory_mapping,
+            blake3,
+        )
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_vectors() {
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(hex::encode(sha256(&[])), empty);
+        assert_eq!(hex::encode(sha256(&[b""])), empty);
+
+        // Slices hash as their concatenation
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(hex::encode(sha256(&[b"abc"])), abc);
+        assert_eq!(hex::encode(sha256(&[b"a", b"", b"bc"])), abc);
+        assert_eq!(hex::encode(sha256(&[b"a", b"b", b"c"])), abc);
+    }
+
+    #[test]
+    fn keccak256_vectors() {
+        let empty = "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+        assert_eq!(hex::encode(keccak256(&[])), empty);
+        assert_eq!(hex::encode(keccak256(&[b""])), empty);
+
+        // Slices hash as their concatenation
+        let abc = "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45";
+        assert_eq!(hex::encode(keccak256(&[b"abc"])), abc);
+        assert_eq!(hex::encode(keccak256(&[b"a", b"", b"bc"])), abc);
+    }
+
+    #[test]
+    fn blake3_vectors() {
+        let empty = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+        assert_eq!(hex::encode(blake3(&[])), empty);
+        assert_eq!(hex::encode(blake3(&[b""])), empty);
+
+        let abc = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+        assert_eq!(hex::encode(blake3(&[b"abc"])), abc);
+        assert_eq!(hex::encode(blake3(&[b"a", b"", b"bc"])), abc);
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Program derived address syscalls, sharing the derivation in `runtime::pda`
+//! with the host so both agree on every address.
+
+use super::{is_overlapping, translate_slice, translate_slice_mut, translate_slices, Error};
+use crate::{compute_budget::CREATE_PROGRAM_ADDRESS_UNITS, SolanaContext};
+use runtime::{
+    pda::{MAX_SEEDS, MAX_SEED_LEN},
+    Pubkey,
+};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+/// Returned to the program when no valid address could be derived.
+const FAILURE: u64 = 1;
+
+/// Translates the seeds and program id shared by both PDA syscalls.
+/// Too many seeds, or a seed that is too long, aborts the program as on Solana.
+fn translate_program_address_inputs<'a>(
+    memory_mapping: &MemoryMapping,
+    seeds_addr: u64,
+    seeds_len: u64,
+    program_id_addr: u64,
+) -> Result<(Vec<&'a [u8]>, Pubkey), Error> {
+    if seeds_len > MAX_SEEDS as u64 {
+        return Err(format!("Too many seeds ({} > {})", seeds_len, MAX_SEEDS).into());
+    }
+    let seeds = translate_slices(memory_mapping, seeds_addr, seeds_len)?;
+    if let Some(seed) = seeds.iter().find(|seed| seed.len() > MAX_SEED_LEN) {
+        return Err(format!("Seed too long ({} > {})", seed.len(), MAX_SEED_LEN).into());
+    }
+    let program_id = Pubkey::try_from(translate_slice(
+        memory_mapping,
+        program_id_addr,
+        size_of::<Pubkey>() as u64,
+    )?)?;
+    Ok((seeds, program_id))
+}
+
+// Implements sol_create_program_address. Writes the derived address and returns 0,
+// or returns 1 if the seeds hash to a point on the ed25519 curve.
+declare_builtin_function!(
+    SyscallCreateProgramAddress,
+    fn rust(
+        context: &mut SolanaContext,
+        seeds_addr: u64,
+        seeds_len: u64,
+        program_id_addr: u64,
+        address_addr: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(CREATE_PROGRAM_ADDRESS_UNITS)?;
+
+        let (seeds, program_id) = translate_program_address_inputs(
+            memory_mapping,
+            seeds_addr,
+            seeds_len,
+            program_id_addr,
+        )?;
+        let Ok(address) = Pubkey::create_program_address(&seeds, &program_id) else {
+            return Ok(FAILURE);
+        };
+
+        translate_slice_mut(memory_mapping, address_addr, size_of::<Pubkey>() as u64)?
+            .copy_from_slice(address.as_ref());
+        Ok(0)
+    }
+);
+
+// Implements sol_try_find_program_address. Searches bump seeds from 255 down,
+// charging the derivation cost for every attempt, and writes the first valid
+// address and its bump seed. Returns 1 if every bump seed lands on the curve.
+declare_builtin_function!(
+    SyscallTryFindProgramAddress,
+    fn rust(
+        context: &mut SolanaContext,
+        seeds_addr: u64,
+        seeds_len: u64,
+        program_id_addr: u64,
+        address_addr: u64,
+        bump_seed_addr: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(CREATE_PROGRAM_ADDRESS_UNITS)?;
+
+        let (seeds, program_id) = translate_program_address_inputs(
+            memory_mapping,
+            seeds_addr,
+            seeds_len,
+            program_id_addr,
+        )?;
+        for bump in (1..=u8::MAX).rev() {
+            let bump_seed = [bump];
+            let mut seeds_with_bump = seeds.clone();
+            seeds_with_bump.push(&bump_seed);
+            if let Ok(address) = Pubkey::create_program_address(&seeds_with_bump, &program_id) {
+                let bump_seed_ref = translate_slice_mut(memory_mapping, bump_seed_addr, 1)?;
+                let address_ref =
+                    translate_slice_mut(memory_mapping, address_addr, size_of::<Pubkey>() as u64)?;
+                if is_overlapping(bump_seed_ref, address_ref) {
+                    return Err("Overlapping copy".into());
+                }
+                bump_seed_ref[0] = bump;
+                address_ref.copy_from_slice(address.as_ref());
+                return Ok(0);
+            }
+            context.consume_checked(CREATE_PROGRAM_ADDRESS_UNITS)?;
+        }
+        Ok(FAILURE)
+    }
+);
@@ -0,0 +1,186 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Poseidon hashing over the BN254 scalar field, with the circom-compatible x^5
+//! parameters from `light-poseidon` that Solana uses for compressed accounts.
+
+use super::{translate_slice_mut, translate_slices};
+use crate::{
+    compute_budget::{POSEIDON_COST_COEFFICIENT_A, POSEIDON_COST_COEFFICIENT_C},
+    SolanaContext,
+};
+use ark_bn254::Fr;
+use light_poseidon::{Poseidon, PoseidonBytesHasher, PoseidonError};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+/// The only parameter set Solana supports: BN254 with the x^5 S-box.
+pub const POSEIDON_BN254_X5: u64 = 0;
+
+// Byte order of the inputs and the resulting hash
+pub const POSEIDON_BIG_ENDIAN: u64 = 0;
+pub const POSEIDON_LITTLE_ENDIAN: u64 = 1;
+
+/// Size of a Poseidon hash.
+pub const POSEIDON_HASH_BYTES: u64 = 32;
+/// Maximum number of inputs hashed in one syscall.
+pub const POSEIDON_MAX_INPUTS: u64 = 12;
+
+// Error codes returned by sol_poseidon, matching Solana's
+const INVALID_NUMBER_OF_INPUTS: u64 = 3;
+const EMPTY_INPUT: u64 = 4;
+const INVALID_INPUT_LENGTH: u64 = 5;
+const BYTES_TO_PRIME_FIELD_ELEMENT: u64 = 6;
+const INPUT_LARGER_THAN_MODULUS: u64 = 7;
+const VEC_TO_ARRAY: u64 = 8;
+const U64_TO_U8: u64 = 9;
+const BYTES_TO_BIG_INT: u64 = 10;
+const INVALID_WIDTH_CIRCOM: u64 = 11;
+
+/// Maps a `light-poseidon` error to the code Solana returns to the program.
+fn error_code(error: PoseidonError) -> u64 {
+    match error {
+        PoseidonError::InvalidNumberOfInputs { .. } => INVALID_NUMBER_OF_INPUTS,
+        PoseidonError::EmptyInput => EMPTY_INPUT,
+        PoseidonError::InvalidInputLength { .. } => INVALID_INPUT_LENGTH,
+        PoseidonError::BytesToPrimeFieldElement { .. } => BYTES_TO_PRIME_FIELD_ELEMENT,
+        PoseidonError::InputLargerThanModulus => INPUT_LARGER_THAN_MODULUS,
+        PoseidonError::VecToArray => VEC_TO_ARRAY,
+        PoseidonError::U64Tou8 => U64_TO_U8,
+        PoseidonError::BytesToBigInt => BYTES_TO_BIG_INT,
+        PoseidonError::InvalidWidthCircom { .. } => INVALID_WIDTH_CIRCOM,
+    }
+}
+
+/// Hashes the inputs as BN254 field elements in the given byte order.
+/// Each input must be at most 32 bytes and smaller than the field modulus.
+pub fn poseidon_hash(endianness: u64, vals: &[&[u8]]) -> Result<[u8; 32], u64> {
+    let mut hasher = Poseidon::<Fr>::new_circom(vals.len()).map_err(error_code)?;
+    match endianness {
+        POSEIDON_LITTLE_ENDIAN => hasher.hash_bytes_le(vals),
+        _ => hasher.hash_bytes_be(vals),
+    }
+    .map_err(error_code)
+}
+
+// Implements sol_poseidon. Unsupported parameters, byte orders or more than 12
+// inputs abort the program; hashing failures are returned as Solana's error codes.
+declare_builtin_function!(
+    SyscallPoseidon,
+    fn rust(
+        context: &mut SolanaContext,
+        parameters: u64,
+        endianness: u64,
+        vals_addr: u64,
+        vals_len: u64,
+        result_addr: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        if parameters != POSEIDON_BN254_X5 {
+            return Err(format!("Invalid Poseidon parameters {}", parameters).into());
+        }
+        if endianness != POSEIDON_BIG_ENDIAN && endianness != POSEIDON_LITTLE_ENDIAN {
+            return Err(format!("Invalid Poseidon endianness {}", endianness).into());
+        }
+        if vals_len > POSEIDON_MAX_INPUTS {
+            context.log_collector.log(&format!(
+                "Poseidon hashing {} sequences is not supported",
+                vals_len
+            ));
+            return Err("Invalid length".into());
+        }
+        context.consume_checked(
+            POSEIDON_COST_COEFFICIENT_A
+                .saturating_mul(vals_len.saturating_mul(vals_len))
+                .saturating_add(POSEIDON_COST_COEFFICIENT_C),
+        )?;
+
+        let hash_result = translate_slice_mut(memory_mapping, result_addr, POSEIDON_HASH_BYTES)?;
+        let vals = translate_slices(memory_mapping, vals_addr, vals_len)?;
+        match poseidon_hash(endianness, &vals) {
+            Ok(hash) => {
+                hash_result.copy_from_slice(&hash);
+                Ok(0)
+            }
+            Err(code) => Ok(code),
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // circomlibjs poseidon([1, ...]) for 1 to 12 inputs, big-endian
+    const ONES_VECTORS: [&str; 12] = [
+        "29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133",
+        "007af346e2d304279e79e0a9f3023f771294a78acb70e73f90afe27cad401e81",
+        "02c0066e10a72abd2b33c3b214cb3e81bcb1b6e30961cd23c202b18673bf2543",
+        "082c9c370a0d24f4416fbc414a37681f78442d27d86385991c17d6fc0c4b7d71",
+        "10389605ae688d4f14db853122c47d66a803c72b41589cb1bf868741b206b9bb",
+        "2a73f679328c3eab724aa3e5bdbf50b39035d7729f135b9709890f85c5dc5e76",
+        "2276310aa7f3343a284214139d9da959be2a31b2c708a5f81954b265e53a30b8",
+        "177e1453c446e1b07d2b4233425147095c4fcabb233d230b6d46a214d95b2884",
+        "0e8fee2fe49da30fdeeb48c42ebb44cc6ee7055f61fbca5e313b8a5fca834c47",
+        "2ec4c65e6378ab8c7330854f4a7077c1ff9260e44885c4b81dd131ad3a86cd96",
+        "00713d41eca635f117d4ecbceb5f3a66dc4142eb70b56765bc358f1bec40bb9b",
+        "14390be0baef249bd47c65ddac65c2e52e8513c081c1cd72c98006098e9a8fbe",
+    ];
+
+    #[test]
+    fn circom_vectors() {
+        let one = [[0u8; 31].as_slice(), &[1]].concat();
+        for (index, expected) in ONES_VECTORS.iter().enumerate() {
+            let inputs = vec![one.as_slice(); index + 1];
+            assert_eq!(
+                hex::encode(poseidon_hash(POSEIDON_BIG_ENDIAN, &inputs).unwrap()),
+                *expected,
+                "{} inputs",
+                index + 1
+            );
+        }
+    }
+
+    #[test]
+    fn endianness() {
+        let expected_be = "0d54e1938f8a8c1c7deb5e0355f26319207b84fe9ca2ce1b26e735c829821990";
+        let hash = poseidon_hash(POSEIDON_BIG_ENDIAN, &[&[1u8; 32], &[2u8; 32]]).unwrap();
+        assert_eq!(hex::encode(hash), expected_be);
+
+        let mut hash = poseidon_hash(POSEIDON_LITTLE_ENDIAN, &[&[1u8; 32], &[2u8; 32]]).unwrap();
+        hash.reverse();
+        assert_eq!(hex::encode(hash), expected_be);
+
+        // Short inputs are zero extended at the most significant end
+        assert_eq!(
+            poseidon_hash(POSEIDON_BIG_ENDIAN, &[&[1, 0, 0, 0], &[1, 0, 0, 0]]),
+            poseidon_hash(POSEIDON_LITTLE_ENDIAN, &[&[0, 0, 0, 1], &[0, 0, 0, 1]]).map(|mut h| {
+                h.reverse();
+                h
+            })
+        );
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!(
+            poseidon_hash(POSEIDON_BIG_ENDIAN, &[&[1u8; 32], &[]]),
+            Err(EMPTY_INPUT)
+        );
+        assert_eq!(
+            poseidon_hash(POSEIDON_BIG_ENDIAN, &[&[1u8; 33]]),
+            Err(INVALID_INPUT_LENGTH)
+        );
+        assert_eq!(
+            poseidon_hash(POSEIDON_BIG_ENDIAN, &[&[0xff; 32]]),
+            Err(INPUT_LARGER_THAN_MODULUS)
+        );
+        assert_eq!(
+            poseidon_hash(POSEIDON_BIG_ENDIAN, &[]),
+            Err(INVALID_WIDTH_CIRCOM)
+        );
+        assert_eq!(
+            poseidon_hash(POSEIDON_BIG_ENDIAN, &[&[1u8][..]; 13]),
+            Err(INVALID_WIDTH_CIRCOM)
+        );
+    }
+}
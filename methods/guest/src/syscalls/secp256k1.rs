@@ -0,0 +1,159 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use super::{translate_slice, translate_slice_mut};
+use crate::{compute_budget::SECP256K1_RECOVER_COST, SolanaContext};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+pub const SECP256K1_HASH_LENGTH: usize = 32;
+pub const SECP256K1_SIGNATURE_LENGTH: usize = 64;
+pub const SECP256K1_PUBLIC_KEY_LENGTH: usize = 64;
+
+// Error codes returned by sol_secp256k1_recover, matching Solana's. Solana's
+// InvalidHash (1) cannot occur as the hash is always read as 32 bytes.
+const INVALID_RECOVERY_ID: u64 = 2;
+const INVALID_SIGNATURE: u64 = 3;
+
+/// Recovers the 64-byte uncompressed public key (without the 0x04 prefix) that signed
+/// `hash`, or returns one of Solana's error codes.
+pub fn secp256k1_recover(
+    hash: &[u8],
+    recovery_id: u64,
+    signature: &[u8],
+) -> Result<[u8; SECP256K1_PUBLIC_KEY_LENGTH], u64> {
+    let mut recovery_id = u8::try_from(recovery_id)
+        .ok()
+        .and_then(RecoveryId::from_byte)
+        .ok_or(INVALID_RECOVERY_ID)?;
+    let mut signature = Signature::from_slice(signature).map_err(|_| INVALID_SIGNATURE)?;
+
+    // Solana accepts high-S signatures; k256 only recovers from normalized ones.
+    // Negating S mirrors R, so the recovery id's y parity flips with it.
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+    }
+
+    let public_key = VerifyingKey::recover_from_prehash(hash, &signature, recovery_id)
+        .map_err(|_| INVALID_SIGNATURE)?;
+    Ok(public_key.to_encoded_point(false).as_bytes()[1..]
+        .try_into()
+        .expect("uncompressed point is 65 bytes"))
+}
+
+// Implements sol_secp256k1_recover for recovering Ethereum-style public keys.
+// Backed by RISC Zero's accelerated k256. Writes the 64-byte uncompressed key
+// (without the 0x04 prefix) and returns 0, or returns one of Solana's error codes.
+declare_builtin_function!(
+    SyscallSecp256k1Recover,
+    fn rust(
+        context: &mut SolanaContext,
+        hash_addr: u64,
+        recovery_id_val: u64,
+        signature_addr: u64,
+        result_addr: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        context.consume_checked(SECP256K1_RECOVER_COST)?;
+
+        let hash = translate_slice(memory_mapping, hash_addr, SECP256K1_HASH_LENGTH as u64)?;
+        let signature = translate_slice(
+            memory_mapping,
+            signature_addr,
+            SECP256K1_SIGNATURE_LENGTH as u64,
+        )?;
+        let result = translate_slice_mut(
+            memory_mapping,
+            result_addr,
+            SECP256K1_PUBLIC_KEY_LENGTH as u64,
+        )?;
+
+        match secp256k1_recover(hash, recovery_id_val, signature) {
+            Ok(public_key) => {
+                result.copy_from_slice(&public_key);
+                Ok(0)
+            }
+            Err(code) => Ok(code),
+        }
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+
+    const HASH: [u8; 32] = [0x5a; 32];
+
+    /// A low-S signature over `HASH`, its recovery id and the signer's public key.
+    fn signed() -> (Signature, RecoveryId, [u8; 64]) {
+        let signing_key = SigningKey::from_bytes(&[0x11; 32].into()).unwrap();
+        let (signature, recovery_id) = signing_key.sign_prehash_recoverable(&HASH).unwrap();
+        let public_key = signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()[1..]
+            .try_into()
+            .unwrap();
+        (signature, recovery_id, public_key)
+    }
+
+    #[test]
+    fn recover() {
+        let (signature, recovery_id, public_key) = signed();
+        assert_eq!(
+            secp256k1_recover(&HASH, recovery_id.to_byte() as u64, &signature.to_bytes()),
+            Ok(public_key)
+        );
+    }
+
+    #[test]
+    fn recover_high_s() {
+        let (signature, recovery_id, public_key) = signed();
+        let (r, s) = signature.split_scalars();
+        let high_s = Signature::from_scalars(r, -s).unwrap();
+        assert!(high_s.normalize_s().is_some());
+
+        // The mirrored signature recovers the same key with the opposite parity
+        let flipped = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+        assert_eq!(
+            secp256k1_recover(&HASH, flipped.to_byte() as u64, &high_s.to_bytes()),
+            Ok(public_key)
+        );
+    }
+
+    #[test]
+    fn invalid_recovery_id() {
+        let (signature, _, _) = signed();
+        for recovery_id in [4, 256, u64::MAX] {
+            assert_eq!(
+                secp256k1_recover(&HASH, recovery_id, &signature.to_bytes()),
+                Err(INVALID_RECOVERY_ID)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_signature() {
+        // Zero scalars are not a signature
+        assert_eq!(
+            secp256k1_recover(&HASH, 0, &[0; 64]),
+            Err(INVALID_SIGNATURE)
+        );
+        // A valid signature that recovers no point: R's x coordinate plus the group
+        // order is past the field modulus
+        let (signature, recovery_id, _) = signed();
+        let reduced = RecoveryId::new(recovery_id.is_y_odd(), true);
+        assert_eq!(
+            secp256k1_recover(&HASH, reduced.to_byte() as u64, &signature.to_bytes()),
+            Err(INVALID_SIGNATURE)
+        );
+        // Scalars at or above the group order are rejected
+        assert_eq!(
+            secp256k1_recover(&HASH, 0, &[0xff; 64]),
+            Err(INVALID_SIGNATURE)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Sysvar syscalls, served from the sysvar cache declared by the host.
+//! The typed getters write the sysvar's `repr(C)` struct, padding included, while
+//! sol_get_sysvar copies a range of the sysvar's account data.
+
+use super::{translate_slice, translate_slice_mut, Error};
+use crate::{
+    compute_budget::{CPI_BYTES_PER_UNIT, MEM_OP_BASE_COST, SYSVAR_BASE_COST},
+    SolanaContext,
+};
+use runtime::{
+    sysvar::{Clock, EpochRewards, EpochSchedule, LastRestartSlot, Rent},
+    Pubkey,
+};
+use solana_sbpf::{declare_builtin_function, memory_region::MemoryMapping};
+
+// Return codes of sol_get_sysvar, matching Solana's
+const OFFSET_LENGTH_EXCEEDS_SYSVAR: u64 = 1;
+const SYSVAR_NOT_FOUND: u64 = 2;
+
+/// A sysvar as laid out in program memory by the typed getters.
+trait SysvarLayout {
+    fn to_c_layout(&self) -> Vec<u8>;
+}
+
+impl SysvarLayout for Clock {
+    fn to_c_layout(&self) -> Vec<u8> {
+        self.to_account_data()
+    }
+}
+
+impl SysvarLayout for Rent {
+    /// u64, f64 and u8, padded to 24 bytes.
+    fn to_c_layout(&self) -> Vec<u8> {
+        let mut bytes = self.to_account_data();
+        bytes.resize(24, 0);
+        bytes
+    }
+}
+
+impl SysvarLayout for EpochSchedule {
+    /// The `warmup` flag is padded to 8 bytes, for 40 bytes in total.
+    fn to_c_layout(&self) -> Vec<u8> {
+        let mut bytes = self.to_account_data();
+        bytes.splice(17..17, [0; 7]);
+        bytes
+    }
+}
+
+impl SysvarLayout for EpochRewards {
+    /// The u128 field is already 16-byte aligned; the struct is padded to 96 bytes.
+    fn to_c_layout(&self) -> Vec<u8> {
+        let mut bytes = self.to_account_data();
+        bytes.resize(96, 0);
+        bytes
+    }
+}
+
+impl SysvarLayout for LastRestartSlot {
+    fn to_c_layout(&self) -> Vec<u8> {
+        self.to_account_data()
+    }
+}
+
+/// Shared body of the typed sysvar getters.
+/// Charges the base cost plus the size of the struct and writes it to `var_addr`.
+fn get_sysvar(
+    context: &mut SolanaContext,
+    sysvar: &impl SysvarLayout,
+    var_addr: u64,
+    memory_mapping: &MemoryMapping,
+) -> Result<u64, Error> {
+    let bytes = sysvar.to_c_layout();
+    context.consume_checked(SYSVAR_BASE_COST.saturating_add(bytes.len() as u64))?;
+
+    translate_slice_mut(memory_mapping, var_addr, bytes.len() as u64)?.copy_from_slice(&bytes);
+    Ok(0)
+}
+
+// Implements sol_get_clock_sysvar.
+declare_builtin_function!(
+    SyscallGetClockSysvar,
+    fn rust(
+        context: &mut SolanaContext,
+        var_addr: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let clock = context.sysvars.clock.clone();
+        get_sysvar(context, &clock, var_addr, memory_mapping)
+    }
+);
+
+// Implements sol_get_rent_sysvar.
+declare_builtin_function!(
+    SyscallGetRentSysvar,
+    fn rust(
+        context: &mut SolanaContext,
+        var_addr: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let rent = context.sysvars.rent.clone();
+        get_sysvar(context, &rent, var_addr, memory_mapping)
+    }
+);
+
+// Implements sol_get_epoch_schedule_sysvar.
+declare_builtin_function!(
+    SyscallGetEpochScheduleSysvar,
+    fn rust(
+        context: &mut SolanaContext,
+        var_addr: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let epoch_schedule = context.sysvars.epoch_schedule.clone();
+        get_sysvar(context, &epoch_schedule, var_addr, memory_mapping)
+    }
+);
+
+// Implements sol_get_epoch_rewards_sysvar.
+declare_builtin_function!(
+    SyscallGetEpochRewardsSysvar,
+    fn rust(
+        context: &mut SolanaContext,
+        var_addr: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let epoch_rewards = context.sysvars.epoch_rewards.clone();
+        get_sysvar(context, &epoch_rewards, var_addr, memory_mapping)
+    }
+);
+
+// Implements sol_get_last_restart_slot.
+declare_builtin_function!(
+    SyscallGetLastRestartSlotSysvar,
+    fn rust(
+        context: &mut SolanaContext,
+        var_addr: u64,
+        _arg2: u64,
+        _arg3: u64,
+        _arg4: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let last_restart_slot = context.sysvars.last_restart_slot.clone();
+        get_sysvar(context, &last_restart_slot, var_addr, memory_mapping)
+    }
+);
+
+// Implements sol_get_sysvar, copying `length` bytes at `offset` of a sysvar's account
+// data. Returns 2 for an unknown sysvar and 1 if the range runs past its end.
+declare_builtin_function!(
+    SyscallGetSysvar,
+    fn rust(
+        context: &mut SolanaContext,
+        sysvar_id_addr: u64,
+        var_addr: u64,
+        offset: u64,
+        length: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, Box<dyn core::error::Error + Send + Sync>> {
+        let sysvar_id_cost = size_of::<Pubkey>() as u64 / CPI_BYTES_PER_UNIT;
+        let sysvar_buf_cost = length / CPI_BYTES_PER_UNIT;
+        context.consume_checked(
+            sysvar_id_cost
+                .saturating_add(sysvar_buf_cost.max(MEM_OP_BASE_COST))
+                .saturating_add(SYSVAR_BASE_COST),
+        )?;
+
+        let var = translate_slice_mut(memory_mapping, var_addr, length)?;
+        let sysvar_id = Pubkey::try_from(translate_slice(
+            memory_mapping,
+            sysvar_id_addr,
+            size_of::<Pubkey>() as u64,
+        )?)?;
+        let end = offset.checked_add(length).ok_or("Arithmetic overflow")?;
+        var_addr.checked_add(length).ok_or("Arithmetic overflow")?;
+
+        let Some(data) = context.sysvars.account_data(&sysvar_id) else {
+            return Ok(SYSVAR_NOT_FOUND);
+        };
+        if end > data.len() as u64 {
+            return Ok(OFFSET_LENGTH_EXCEEDS_SYSVAR);
+        }
+        var.copy_from_slice(&data[offset as usize..end as usize]);
+        Ok(0)
+    }
+);
@@ -0,0 +1,488 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! The System Program, run natively in the guest when a program invokes it.
+//! Supports create_account, assign, transfer and allocate with the same signer,
+//! ownership and balance checks as Solana's builtin, logging the same messages.
+
+use crate::{log_collector::LogCollector, serializer::MAX_PERMITTED_DATA_LENGTH};
+use runtime::{journal::ExecutionResult, Account, Pubkey};
+use std::fmt;
+
+// Instruction discriminants, as bincode encodes SystemInstruction
+const CREATE_ACCOUNT: u32 = 0;
+const ASSIGN: u32 = 1;
+const TRANSFER: u32 = 2;
+const ALLOCATE: u32 = 8;
+
+/// Address of the System Program, 11111111111111111111111111111111.
+pub fn id() -> Pubkey {
+    Pubkey::default()
+}
+
+/// Why the System Program refused an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemProgramError {
+    /// An account to be created or allocated already holds lamports, data or an owner.
+    AccountAlreadyInUse,
+    /// The funding account cannot cover the transfer.
+    ResultWithNegativeLamports,
+    /// Allocation larger than `MAX_PERMITTED_DATA_LENGTH`.
+    InvalidAccountDataLength,
+    MissingRequiredSignature,
+    NotEnoughAccountKeys,
+    InvalidInstructionData,
+    InvalidArgument,
+    /// Crediting the recipient would overflow its lamports.
+    ArithmeticOverflow,
+}
+
+impl fmt::Display for SystemProgramError {
+    /// Formats the error the way Solana reports it in program logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SystemProgramError::AccountAlreadyInUse => f.write_str("custom program error: 0x0"),
+            SystemProgramError::ResultWithNegativeLamports => {
+                f.write_str("custom program error: 0x1")
+            }
+            SystemProgramError::InvalidAccountDataLength => {
+                f.write_str("custom program error: 0x3")
+            }
+            SystemProgramError::MissingRequiredSignature => {
+                f.write_str("missing required signature for instruction")
+            }
+            SystemProgramError::NotEnoughAccountKeys => {
+                f.write_str("insufficient account keys for instruction")
+            }
+            SystemProgramError::InvalidInstructionData => f.write_str("invalid instruction data"),
+            SystemProgramError::InvalidArgument => f.write_str("invalid program argument"),
+            SystemProgramError::ArithmeticOverflow => f.write_str("Program arithmetic overflowed"),
+        }
+    }
+}
+
+impl From<SystemProgramError> for ExecutionResult {
+    /// A builtin leaves no value in `r0`, so its errors are reported as aborts
+    /// carrying the instruction error Solana would log.
+    fn from(error: SystemProgramError) -> Self {
+        ExecutionResult::Aborted(error.to_string())
+    }
+}
+
+/// A decoded System Program instruction.
+#[derive(Debug, PartialEq, Eq)]
+enum SystemInstruction {
+    CreateAccount {
+        lamports: u64,
+        space: u64,
+        owner: Pubkey,
+    },
+    Assign {
+        owner: Pubkey,
+    },
+    Transfer {
+        lamports: u64,
+    },
+    Allocate {
+        space: u64,
+    },
+}
+
+impl SystemInstruction {
+    /// Decodes the bincode encoding of a supported instruction. Trailing bytes are
+    /// ignored, as they are by bincode.
+    fn unpack(data: &[u8]) -> Option<Self> {
+        let u64_at = |offset: usize| {
+            data.get(offset..offset + size_of::<u64>())
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+        };
+        let pubkey_at = |offset: usize| {
+            data.get(offset..offset + size_of::<Pubkey>())
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+        };
+        let discriminant = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+        match discriminant {
+            CREATE_ACCOUNT => Some(SystemInstruction::CreateAccount {
+                lamports: u64_at(4)?,
+                space: u64_at(12)?,
+                owner: pubkey_at(20)?,
+            }),
+            ASSIGN => Some(SystemInstruction::Assign {
+                owner: pubkey_at(4)?,
+            }),
+            TRANSFER => Some(SystemInstruction::Transfer {
+                lamports: u64_at(4)?,
+            }),
+            ALLOCATE => Some(SystemInstruction::Allocate { space: u64_at(4)? }),
+            _ => None,
+        }
+    }
+}
+
+/// Runs a System Program instruction over its accounts, updating them in place.
+/// An account passed more than once is updated at every position.
+pub fn process_instruction(
+    log_collector: &mut LogCollector,
+    accounts: &mut [Account],
+    instruction_data: &[u8],
+) -> Result<(), SystemProgramError> {
+    let Some(instruction) = SystemInstruction::unpack(instruction_data) else {
+        log_collector.log("Unsupported or malformed system instruction");
+        return Err(SystemProgramError::InvalidInstructionData);
+    };
+
+    let mut state = SystemAccounts { accounts };
+    match instruction {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let from = state.index(0)?;
+            let to = state.index(1)?;
+            // If it looks like the `to` account is already in use, bail
+            if state.accounts[to].lamports > 0 {
+                log_collector.log(&format!(
+                    "Create Account: account {} already in use",
+                    state.accounts[to].pubkey
+                ));
+                return Err(SystemProgramError::AccountAlreadyInUse);
+            }
+            allocate(log_collector, &mut state, to, space)?;
+            assign(log_collector, &mut state, to, owner)?;
+            transfer(log_collector, &mut state, from, to, lamports)?;
+        }
+        SystemInstruction::Assign { owner } => {
+            let account = state.index(0)?;
+            assign(log_collector, &mut state, account, owner)?;
+        }
+        SystemInstruction::Transfer { lamports } => {
+            let from = state.index(0)?;
+            let to = state.index(1)?;
+            transfer(log_collector, &mut state, from, to, lamports)?;
+        }
+        SystemInstruction::Allocate { space } => {
+            let account = state.index(0)?;
+            allocate(log_collector, &mut state, account, space)?;
+        }
+    }
+    state.sync_duplicates();
+    Ok(())
+}
+
+/// Instruction accounts, addressed by the first position of each key so that
+/// duplicates share one state while the instruction runs.
+struct SystemAccounts<'a> {
+    accounts: &'a mut [Account],
+}
+
+impl SystemAccounts<'_> {
+    /// Position of the first occurrence of the key at instruction account `index`.
+    fn index(&self, index: usize) -> Result<usize, SystemProgramError> {
+        let pubkey = self
+            .accounts
+            .get(index)
+            .ok_or(SystemProgramError::NotEnoughAccountKeys)?
+            .pubkey;
+        Ok(self
+            .accounts
+            .iter()
+            .position(|account| account.pubkey == pubkey)
+            .expect("account is present"))
+    }
+
+    /// Whether any occurrence of the account signed the instruction.
+    fn is_signer(&self, index: usize) -> bool {
+        let pubkey = self.accounts[index].pubkey;
+        self.accounts
+            .iter()
+            .any(|account| account.pubkey == pubkey && account.is_signer)
+    }
+
+    /// Copies the state of each first occurrence to the later occurrences of its key.
+    fn sync_duplicates(&mut self) {
+        for index in 0..self.accounts.len() {
+            let first = self.index(index).expect("index is in bounds");
+            if first != index {
+                let original = self.accounts[first].clone();
+                let duplicate = &mut self.accounts[index];
+                duplicate.lamports = original.lamports;
+                duplicate.data = original.data;
+                duplicate.owner = original.owner;
+            }
+        }
+    }
+}
+
+fn allocate(
+    log_collector: &mut LogCollector,
+    state: &mut SystemAccounts,
+    index: usize,
+    space: u64,
+) -> Result<(), SystemProgramError> {
+    let account = &state.accounts[index];
+    if !state.is_signer(index) {
+        log_collector.log(&format!(
+            "Allocate: 'to' account {} must sign",
+            account.pubkey
+        ));
+        return Err(SystemProgramError::MissingRequiredSignature);
+    }
+    // If it looks like the account is already in use, bail
+    if !account.data.is_empty() || account.owner != id() {
+        log_collector.log(&format!(
+            "Allocate: account {} already in use",
+            account.pubkey
+        ));
+        return Err(SystemProgramError::AccountAlreadyInUse);
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH as u64 {
+        log_collector.log(&format!(
+            "Allocate: requested {}, max allowed {}",
+            space, MAX_PERMITTED_DATA_LENGTH
+        ));
+        return Err(SystemProgramError::InvalidAccountDataLength);
+    }
+
+    state.accounts[index].data = vec![0; space as usize];
+    Ok(())
+}
+
+fn assign(
+    log_collector: &mut LogCollector,
+    state: &mut SystemAccounts,
+    index: usize,
+    owner: Pubkey,
+) -> Result<(), SystemProgramError> {
+    // No work to do, just return
+    if state.accounts[index].owner == owner {
+        return Ok(());
+    }
+    if !state.is_signer(index) {
+        log_collector.log(&format!(
+            "Assign: account {} must sign",
+            state.accounts[index].pubkey
+        ));
+        return Err(SystemProgramError::MissingRequiredSignature);
+    }
+
+    state.accounts[index].owner = owner;
+    Ok(())
+}
+
+fn transfer(
+    log_collector: &mut LogCollector,
+    state: &mut SystemAccounts,
+    from: usize,
+    to: usize,
+    lamports: u64,
+) -> Result<(), SystemProgramError> {
+    if !state.is_signer(from) {
+        log_collector.log(&format!(
+            "Transfer: `from` account {} must sign",
+            state.accounts[from].pubkey
+        ));
+        return Err(SystemProgramError::MissingRequiredSignature);
+    }
+    if !state.accounts[from].data.is_empty() {
+        log_collector.log("Transfer: `from` must not carry data");
+        return Err(SystemProgramError::InvalidArgument);
+    }
+    if lamports > state.accounts[from].lamports {
+        log_collector.log(&format!(
+            "Transfer: insufficient lamports {}, need {}",
+            state.accounts[from].lamports, lamports
+        ));
+        return Err(SystemProgramError::ResultWithNegativeLamports);
+    }
+
+    state.accounts[from].lamports -= lamports;
+    state.accounts[to].lamports = state.accounts[to]
+        .lamports
+        .checked_add(lamports)
+        .ok_or(SystemProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(seed: u8, lamports: u64, is_signer: bool) -> Account {
+        Account {
+            pubkey: Pubkey::from([seed; 32]),
+            is_signer,
+            is_writable: true,
+            lamports,
+            data: vec![],
+            owner: id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn transfer_data(lamports: u64) -> Vec<u8> {
+        [&TRANSFER.to_le_bytes()[..], &lamports.to_le_bytes()].concat()
+    }
+
+    #[test]
+    fn create_account() {
+        let owner = Pubkey::from([9; 32]);
+        let data = [
+            &CREATE_ACCOUNT.to_le_bytes()[..],
+            &100u64.to_le_bytes(),
+            &16u64.to_le_bytes(),
+            owner.as_ref(),
+        ]
+        .concat();
+        let mut accounts = [account(1, 1_000, true), account(2, 0, true)];
+        process_instruction(&mut LogCollector::default(), &mut accounts, &data).unwrap();
+        assert_eq!(accounts[0].lamports, 900);
+        assert_eq!(accounts[1].lamports, 100);
+        assert_eq!(accounts[1].data, vec![0; 16]);
+        assert_eq!(accounts[1].owner, owner);
+
+        // The new account now holds lamports, so it cannot be created again
+        assert_eq!(
+            process_instruction(&mut LogCollector::default(), &mut accounts, &data),
+            Err(SystemProgramError::AccountAlreadyInUse)
+        );
+    }
+
+    #[test]
+    fn transfer_checks() {
+        let mut accounts = [account(1, 10, false), account(2, 0, false)];
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &transfer_data(5)
+            ),
+            Err(SystemProgramError::MissingRequiredSignature)
+        );
+
+        accounts[0].is_signer = true;
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &transfer_data(11)
+            ),
+            Err(SystemProgramError::ResultWithNegativeLamports)
+        );
+        process_instruction(
+            &mut LogCollector::default(),
+            &mut accounts,
+            &transfer_data(10),
+        )
+        .unwrap();
+        assert_eq!((accounts[0].lamports, accounts[1].lamports), (0, 10));
+    }
+
+    #[test]
+    fn transfer_to_self() {
+        let mut accounts = [account(1, 10, true), account(1, 10, false)];
+        process_instruction(
+            &mut LogCollector::default(),
+            &mut accounts,
+            &transfer_data(4),
+        )
+        .unwrap();
+        assert_eq!((accounts[0].lamports, accounts[1].lamports), (10, 10));
+    }
+
+    #[test]
+    fn unsupported_instruction() {
+        let mut accounts = [account(1, 10, true)];
+        assert_eq!(
+            process_instruction(&mut LogCollector::default(), &mut accounts, &[3, 0, 0, 0]),
+            Err(SystemProgramError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn allocate_checks() {
+        let allocate_data =
+            |space: u64| [&ALLOCATE.to_le_bytes()[..], &space.to_le_bytes()].concat();
+        let mut accounts = [account(1, 0, false)];
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &allocate_data(8)
+            ),
+            Err(SystemProgramError::MissingRequiredSignature)
+        );
+
+        accounts[0].is_signer = true;
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &allocate_data(MAX_PERMITTED_DATA_LENGTH as u64 + 1)
+            ),
+            Err(SystemProgramError::InvalidAccountDataLength)
+        );
+        process_instruction(
+            &mut LogCollector::default(),
+            &mut accounts,
+            &allocate_data(8),
+        )
+        .unwrap();
+        assert_eq!(accounts[0].data, vec![0; 8]);
+
+        // An account with data is already in use
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &allocate_data(8)
+            ),
+            Err(SystemProgramError::AccountAlreadyInUse)
+        );
+
+        // As is one owned by another program
+        let mut accounts = [account(1, 0, true)];
+        accounts[0].owner = Pubkey::from([9; 32]);
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &allocate_data(8)
+            ),
+            Err(SystemProgramError::AccountAlreadyInUse)
+        );
+    }
+
+    #[test]
+    fn assign_checks() {
+        let owner = Pubkey::from([9; 32]);
+        let assign_data = [&ASSIGN.to_le_bytes()[..], owner.as_ref()].concat();
+        let mut accounts = [account(1, 0, false)];
+        assert_eq!(
+            process_instruction(&mut LogCollector::default(), &mut accounts, &assign_data),
+            Err(SystemProgramError::MissingRequiredSignature)
+        );
+
+        accounts[0].is_signer = true;
+        process_instruction(&mut LogCollector::default(), &mut accounts, &assign_data).unwrap();
+        assert_eq!(accounts[0].owner, owner);
+
+        // Assigning the current owner needs no signature
+        accounts[0].is_signer = false;
+        process_instruction(&mut LogCollector::default(), &mut accounts, &assign_data).unwrap();
+    }
+
+    #[test]
+    fn transfer_overflow() {
+        let mut accounts = [account(1, 10, true), account(2, u64::MAX, false)];
+        assert_eq!(
+            process_instruction(
+                &mut LogCollector::default(),
+                &mut accounts,
+                &transfer_data(1)
+            ),
+            Err(SystemProgramError::ArithmeticOverflow)
+        );
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Transaction execution. Each instruction in the message runs as a top-level
+//! invocation against the transaction's shared accounts, so state carries from one
+//! instruction to the next, and the first failure rolls every account back.
+
+use crate::{invoke, system_program, SolanaContext};
+use risc0_zkvm::guest::env;
+use runtime::{journal::ExecutionResult, transaction::Message, Account, Pubkey};
+use solana_sbpf::elf::Executable;
+
+/// Runs the message's instructions in order over `context.transaction_accounts`, which
+/// must hold one account per account key. `executable` runs the instructions invoking
+/// `program_id`; other programs are loaded from the input.
+/// Returns the SBPF instructions executed across all programs, the transaction result
+/// and the accounts in account key order.
+pub fn execute_transaction(
+    context: &mut SolanaContext,
+    executable: &Executable<SolanaContext>,
+    program_id: Pubkey,
+    message: &Message,
+) -> (u64, ExecutionResult, Vec<Account>) {
+    let pre_accounts = context.transaction_accounts.clone();
+    let mut instruction_count = 0;
+
+    for (index, instruction) in message.instructions.iter().enumerate() {
+        let instruction_program_id = message.account_keys[instruction.program_id_index as usize];
+        // Instruction accounts take their privileges from the message header
+        let accounts: Vec<Account> = instruction
+            .accounts
+            .iter()
+            .map(|&account_index| {
+                let account_index = account_index as usize;
+                let state = context
+                    .account(&message.account_keys[account_index])
+                    .expect("every account key is loaded");
+                Account {
+                    is_signer: message.is_signer(account_index),
+                    is_writable: message.is_writable(account_index),
+                    ..state.clone()
+                }
+            })
+            .collect();
+
+        let result = if instruction_program_id == system_program::id() {
+            invoke::execute_system_program(context, accounts, &instruction.data).0
+        } else if instruction_program_id == program_id {
+            let (count, result, _) = invoke::execute_program(
+                context,
+                executable,
+                program_id,
+                accounts,
+                &instruction.data,
+            );
+            instruction_count += count;
+            result
+        } else {
+            match context.load_program(&instruction_program_id) {
+                Ok(callee) => {
+                    let (count, result, _) = invoke::execute_program(
+                        context,
+                        &callee,
+                        instruction_program_id,
+                        accounts,
+                        &instruction.data,
+                    );
+                    instruction_count += count;
+                    result
+                }
+                Err(e) => ExecutionResult::Aborted(e),
+            }
+        };
+
+        if !result.is_success() {
+            env::log(&format!(
+                "Error processing instruction {}: {}",
+                index, result
+            ));
+            context.transaction_accounts = pre_accounts.clone();
+            return (instruction_count, result, pre_accounts);
+        }
+    }
+
+    (
+        instruction_count,
+        ExecutionResult::Returned(0),
+        context.transaction_accounts.clone(),
+    )
+}
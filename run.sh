@@ -37,7 +37,7 @@ echo ""
 # Step 3: Run the zkVM prover in development mode
 echo "[3/3] Running zkVM prover (dev mode)..."
 echo "---------------------------------------"
-RISC0_DEV_MODE=1 cargo run --release
+RISC0_DEV_MODE=1 cargo run --release -- prove minimal-sol/target/deploy/minimal_sol.so
 
 echo ""
 echo "========================================="
@@ -0,0 +1,160 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use serde::{Deserialize, Serialize};
+
+use crate::{sysvar::SysvarCache, transaction::Message, Account, Pubkey};
+
+/// Solana's default per-instruction compute budget.
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+/// Smallest heap frame a program can request.
+pub const MIN_HEAP_FRAME_BYTES: u32 = 32 * 1024;
+/// Largest heap frame a program can request.
+pub const MAX_HEAP_FRAME_BYTES: u32 = 256 * 1024;
+/// Requested heap frames must be a multiple of this size.
+pub const HEAP_FRAME_GRANULARITY: u32 = 1024;
+/// Solana's default program heap size.
+pub const DEFAULT_HEAP_SIZE: u32 = MIN_HEAP_FRAME_BYTES;
+/// Maximum depth of SBPF-to-SBPF calls.
+pub const DEFAULT_MAX_CALL_DEPTH: u32 = 64;
+/// Size of a single SBPF stack frame.
+pub const DEFAULT_STACK_FRAME_SIZE: u32 = 4_096;
+/// Oldest SBPF version accepted by default (v0, the legacy format).
+pub const DEFAULT_MIN_SBPF_VERSION: u8 = 0;
+/// Newest SBPF version accepted by default (v3).
+pub const DEFAULT_MAX_SBPF_VERSION: u8 = 3;
+/// Newest SBPF version the loader supports.
+pub const MAX_SUPPORTED_SBPF_VERSION: u8 = 3;
+
+/// Input envelope written by the host after the program bytecode.
+/// Carries everything the guest serializer needs to build the program's input region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Input {
+    /// Address the program is invoked as; derived from the ELF hash when not given.
+    pub program_id: Option<Pubkey>,
+    pub accounts: Vec<Account>,
+    pub instruction_data: Vec<u8>,
+    pub parameters: ExecutionParameters,
+    /// Sysvars served to the program.
+    pub sysvars: SysvarCache,
+    /// Programs the invoked program may call through cross-program invocation.
+    pub programs: Vec<Program>,
+    /// Transaction to run instead of a single instruction. `accounts` then holds the
+    /// state of each of the message's account keys, in order, and the bytecode runs
+    /// for the instructions that invoke `program_id`.
+    pub message: Option<Message>,
+    /// Commit the full program logs to the journal, not just their digest.
+    pub commit_logs: bool,
+}
+
+/// Compiled program that can be the target of a cross-program invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Program {
+    pub program_id: Pubkey,
+    pub elf: Vec<u8>,
+}
+
+/// Compute budget and VM settings for an execution.
+/// Lets a proof reproduce the limits requested with compute budget instructions on chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionParameters {
+    pub compute_unit_limit: u64,
+    /// Requested heap frame, as set with `ComputeBudgetInstruction::request_heap_frame`.
+    pub heap_size: u32,
+    pub max_call_depth: u32,
+    pub stack_frame_size: u32,
+    pub min_sbpf_version: u8,
+    pub max_sbpf_version: u8,
+}
+
+impl Default for ExecutionParameters {
+    fn default() -> Self {
+        Self {
+            compute_unit_limit: DEFAULT_COMPUTE_UNIT_LIMIT,
+            heap_size: DEFAULT_HEAP_SIZE,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            stack_frame_size: DEFAULT_STACK_FRAME_SIZE,
+            min_sbpf_version: DEFAULT_MIN_SBPF_VERSION,
+            max_sbpf_version: DEFAULT_MAX_SBPF_VERSION,
+        }
+    }
+}
+
+impl ExecutionParameters {
+    /// Rejects a heap frame Solana's compute budget program would refuse, and VM
+    /// settings the SBPF loader cannot run with.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_HEAP_FRAME_BYTES..=MAX_HEAP_FRAME_BYTES).contains(&self.heap_size)
+            || !self.heap_size.is_multiple_of(HEAP_FRAME_GRANULARITY)
+        {
+            return Err(format!(
+                "Invalid heap frame {}: must be a multiple of {} between {} and {} bytes",
+                self.heap_size, HEAP_FRAME_GRANULARITY, MIN_HEAP_FRAME_BYTES, MAX_HEAP_FRAME_BYTES
+            ));
+        }
+        if self.max_sbpf_version > MAX_SUPPORTED_SBPF_VERSION {
+            return Err(format!(
+                "Unsupported SBPF version {}: the newest supported is {}",
+                self.max_sbpf_version, MAX_SUPPORTED_SBPF_VERSION
+            ));
+        }
+        if self.min_sbpf_version > self.max_sbpf_version {
+            return Err(format!(
+                "Invalid SBPF version range: minimum {} is above maximum {}",
+                self.min_sbpf_version, self.max_sbpf_version
+            ));
+        }
+        if self.max_call_depth == 0 {
+            return Err("Invalid max call depth: must be at least 1".to_string());
+        }
+        // Stack frame gaps are addressed by shifting, so frames must be a power of two
+        if !self.stack_frame_size.is_power_of_two() {
+            return Err(format!(
+                "Invalid stack frame size {}: must be a power of two",
+                self.stack_frame_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_execution_parameters() {
+        assert_eq!(ExecutionParameters::default().validate(), Ok(()));
+
+        let invalid = [
+            ExecutionParameters {
+                heap_size: MIN_HEAP_FRAME_BYTES + 1,
+                ..Default::default()
+            },
+            ExecutionParameters {
+                max_sbpf_version: MAX_SUPPORTED_SBPF_VERSION + 1,
+                ..Default::default()
+            },
+            ExecutionParameters {
+                min_sbpf_version: 2,
+                max_sbpf_version: 1,
+                ..Default::default()
+            },
+            ExecutionParameters {
+                max_call_depth: 0,
+                ..Default::default()
+            },
+            ExecutionParameters {
+                stack_frame_size: 0,
+                ..Default::default()
+            },
+            ExecutionParameters {
+                stack_frame_size: 3_000,
+                ..Default::default()
+            },
+        ];
+        for parameters in invalid {
+            assert!(parameters.validate().is_err(), "{:?}", parameters);
+        }
+    }
+}
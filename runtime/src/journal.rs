@@ -0,0 +1,109 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{input::ExecutionParameters, sysvar::SysvarCache, Account, Pubkey};
+
+/// Version of the journal layout committed by the guest.
+/// Bumped whenever a field is added, removed or changes meaning.
+pub const JOURNAL_VERSION: u32 = 8;
+
+/// SHA-256 digest committed in the journal.
+pub type Hash = [u8; 32];
+
+/// Public output of a proven Solana program execution.
+/// Binds a receipt to the program, its inputs and the resulting state transition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Journal {
+    pub version: u32,
+    pub program_hash: Hash,
+    pub program_id: Pubkey,
+    pub instruction_data_hash: Hash,
+    /// SHA-256 of the transaction message in Solana's wire format, when a whole
+    /// transaction was executed rather than a single instruction.
+    pub message_hash: Option<Hash>,
+    pub parameters: ExecutionParameters,
+    /// Sysvars the program was served.
+    pub sysvars: SysvarCache,
+    /// Programs available to cross-program invocation.
+    pub programs: Vec<ProgramHash>,
+    pub input_accounts_hash: Hash,
+    pub output_accounts_hash: Hash,
+    pub output_accounts: Vec<Account>,
+    pub result: ExecutionResult,
+    pub compute_units_consumed: u64,
+    pub instruction_count: u64,
+    /// SHA-256 of the program logs joined with newlines.
+    pub log_digest: Hash,
+    /// Full program logs, present when requested by the host.
+    pub logs: Option<Vec<String>>,
+    /// Data last set with `sol_set_return_data`, if any.
+    pub return_data: Option<ReturnData>,
+}
+
+/// Program available to cross-program invocation, identified by the hash of its ELF.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramHash {
+    pub program_id: Pubkey,
+    pub program_hash: Hash,
+}
+
+/// Return data set by a program, together with the program that set it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReturnData {
+    pub program_id: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// Outcome of running the program in the SBPF VM.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionResult {
+    /// The program returned normally with the value left in `r0`.
+    /// Zero is success, anything else is the program's error code.
+    Returned(u64),
+    /// The VM stopped the program (memory violation, compute budget exceeded, syscall error...).
+    Aborted(String),
+    /// The program finished but the runtime refused the account state it left behind.
+    Rejected(String),
+}
+
+impl ExecutionResult {
+    /// Whether the program completed and reported success.
+    pub fn is_success(&self) -> bool {
+        matches!(self, ExecutionResult::Returned(0))
+    }
+}
+
+impl fmt::Display for ExecutionResult {
+    /// Formats the result the way Solana reports instruction errors in program logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionResult::Returned(0) => write!(f, "success"),
+            ExecutionResult::Returned(code) if *code <= u32::MAX as u64 => {
+                write!(f, "custom program error: {:#x}", code)
+            }
+            ExecutionResult::Returned(code) => write!(f, "program error: {:#x}", code),
+            ExecutionResult::Aborted(reason) | ExecutionResult::Rejected(reason) => {
+                write!(f, "{}", reason)
+            }
+        }
+    }
+}
+
+/// Canonical byte encoding of account state used for the journal's account hashes.
+/// Covers what a program can observe or change, not the instruction's signer/writable flags.
+pub fn encode_account_state(accounts: &[Account]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for account in accounts {
+        bytes.extend_from_slice(account.pubkey.as_ref());
+        bytes.extend_from_slice(account.owner.as_ref());
+        bytes.extend_from_slice(&account.lamports.to_le_bytes());
+        bytes.push(account.executable as u8);
+        bytes.extend_from_slice(&account.rent_epoch.to_le_bytes());
+        bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&account.data);
+    }
+    bytes
+}
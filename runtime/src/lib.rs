@@ -1,11 +1,21 @@
 // Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
 // SPDX-License-Identifier: MIT
 
-/// Minimal runtime types for Solana program execution in zkVM.
+//! Minimal runtime types for Solana program execution in zkVM.
+//! Shared between the host and the guest so both sides agree on the wire format.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub mod input;
+pub mod journal;
+pub mod pda;
+pub mod sysvar;
+pub mod transaction;
 
 /// Represents a Solana account with all necessary metadata.
 /// Mirrors the on-chain account structure.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Account {
     pub pubkey: Pubkey,
     pub is_signer: bool,
@@ -19,16 +29,47 @@ pub struct Account {
 
 /// 32-byte public key used throughout Solana.
 /// Supports base58 string conversion for human-readable addresses.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub struct Pubkey([u8; 32]);
 
-impl Pubkey {
+impl AsRef<[u8]> for Pubkey {
     /// Returns the underlying byte array.
-    pub(crate) fn as_ref(&self) -> &[u8] {
+    fn as_ref(&self) -> &[u8] {
         &self.0
     }
 }
 
+impl fmt::Display for Pubkey {
+    /// Formats the key as a base58 string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+impl Pubkey {
+    /// Creates a key from its bytes, usable in constants.
+    pub const fn new_from_array(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<[u8; 32]> for Pubkey {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for Pubkey {
+    type Error = String;
+
+    /// Converts a 32-byte slice to a Pubkey.
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; 32]>::try_from(value)
+            .map(Self)
+            .map_err(|_| format!("Invalid pubkey length: {}", value.len()))
+    }
+}
+
 impl TryFrom<String> for Pubkey {
     type Error = String;
 
@@ -0,0 +1,149 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Program derived addresses, derived exactly as Solana does so the host can
+//! compute the same addresses the guest's PDA syscalls return.
+
+use crate::Pubkey;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Maximum number of seeds in a program address.
+pub const MAX_SEEDS: usize = 16;
+/// Maximum length in bytes of a single seed.
+pub const MAX_SEED_LEN: usize = 32;
+/// Suffix hashed after the seeds and program id.
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Reasons a program address cannot be derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PubkeyError {
+    /// More than `MAX_SEEDS` seeds, or a seed longer than `MAX_SEED_LEN`.
+    MaxSeedLengthExceeded,
+    /// The seeds hash to a point on the ed25519 curve.
+    InvalidSeeds,
+}
+
+impl fmt::Display for PubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PubkeyError::MaxSeedLengthExceeded => f.write_str("Length of the seed is too long"),
+            PubkeyError::InvalidSeeds => {
+                f.write_str("Provided seeds do not result in a valid address")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PubkeyError {}
+
+impl Pubkey {
+    /// Whether the key decompresses to a point on the ed25519 curve.
+    /// Program derived addresses are never on the curve, so no private key exists for them.
+    pub fn is_on_curve(&self) -> bool {
+        CompressedEdwardsY(self.0).decompress().is_some()
+    }
+
+    /// Derives the program address for the given seeds, failing if it lands on the curve.
+    pub fn create_program_address(
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Result<Pubkey, PubkeyError> {
+        if seeds.len() > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+            return Err(PubkeyError::MaxSeedLengthExceeded);
+        }
+
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update(program_id.as_ref());
+        hasher.update(PDA_MARKER);
+        let address = Pubkey(hasher.finalize().into());
+
+        if address.is_on_curve() {
+            return Err(PubkeyError::InvalidSeeds);
+        }
+        Ok(address)
+    }
+
+    /// Finds the first valid program address, appending a bump seed counting down from 255 to 1.
+    /// Returns the address and its bump seed, or `None` if no bump seed yields one.
+    pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+        (1..=u8::MAX).rev().find_map(|bump| {
+            let bump_seed = [bump];
+            let mut seeds_with_bump = seeds.to_vec();
+            seeds_with_bump.push(&bump_seed);
+            Pubkey::create_program_address(&seeds_with_bump, program_id)
+                .ok()
+                .map(|address| (address, bump))
+        })
+    }
+
+    /// Finds the program address and bump seed, panicking in the vanishingly
+    /// unlikely case that no bump seed yields one.
+    pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::try_find_program_address(seeds, program_id)
+            .expect("Unable to find a viable program address bump seed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgradeable_loader() -> Pubkey {
+        Pubkey::try_from("BPFLoaderUpgradeab1e11111111111111111111111".to_string()).unwrap()
+    }
+
+    #[test]
+    fn create_program_address_vectors() {
+        // From the Solana SDK's create_program_address tests
+        let program_id = upgradeable_loader();
+        for (seeds, expected) in [
+            (
+                [b"".as_ref(), &[1]],
+                "BwqrghZA2htAcqq8dzP1WDAhTXYTYWj7CHxF5j7TDBAe",
+            ),
+            (
+                ["☉".as_ref(), &[0]],
+                "13yWmRpaTR4r5nAktwLqMpRNr28tnVUZw26rTvPSSB19",
+            ),
+            (
+                [b"Talking".as_ref(), b"Squirrels"],
+                "2fnQrngrQT4SeLcdToJAD96phoEjNL2man2kfRLCASVk",
+            ),
+        ] {
+            assert_eq!(
+                Pubkey::create_program_address(&seeds, &program_id),
+                Ok(Pubkey::try_from(expected.to_string()).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn seed_limits() {
+        let program_id = upgradeable_loader();
+        assert_eq!(
+            Pubkey::create_program_address(&[&[0; MAX_SEED_LEN + 1]], &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert_eq!(
+            Pubkey::create_program_address(&[b"".as_ref(); MAX_SEEDS + 1], &program_id),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert!(Pubkey::create_program_address(&[&[0; MAX_SEED_LEN]], &program_id).is_ok());
+    }
+
+    #[test]
+    fn find_program_address() {
+        let program_id = upgradeable_loader();
+        let (address, bump) = Pubkey::find_program_address(&[b"Lil'", b"Bits"], &program_id);
+        assert!(!address.is_on_curve());
+        assert_eq!(
+            Pubkey::create_program_address(&[b"Lil'", b"Bits", &[bump]], &program_id),
+            Ok(address)
+        );
+    }
+}
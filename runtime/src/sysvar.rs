@@ -0,0 +1,273 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Sysvars served to programs by the guest's sysvar syscalls.
+//! The host declares them in the input and the guest commits them in the journal,
+//! so a proof states the slot, timestamp and cluster parameters it ran against.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{journal::Hash, Pubkey};
+
+/// Address of the Clock sysvar, SysvarC1ock11111111111111111111111111111111.
+pub const CLOCK_ID: Pubkey = Pubkey::new_from_array([
+    6, 167, 213, 23, 24, 199, 116, 201, 40, 86, 99, 152, 105, 29, 94, 182, 139, 94, 184, 163, 155,
+    75, 109, 92, 115, 85, 91, 33, 0, 0, 0, 0,
+]);
+/// Address of the Rent sysvar, SysvarRent111111111111111111111111111111111.
+pub const RENT_ID: Pubkey = Pubkey::new_from_array([
+    6, 167, 213, 23, 25, 44, 92, 81, 33, 140, 201, 76, 61, 74, 241, 127, 88, 218, 238, 8, 155, 161,
+    253, 68, 227, 219, 217, 138, 0, 0, 0, 0,
+]);
+/// Address of the EpochSchedule sysvar, SysvarEpochSchedu1e111111111111111111111111.
+pub const EPOCH_SCHEDULE_ID: Pubkey = Pubkey::new_from_array([
+    6, 167, 213, 23, 24, 220, 63, 238, 2, 211, 228, 127, 1, 0, 248, 176, 84, 247, 148, 46, 96, 89,
+    30, 63, 80, 135, 25, 168, 5, 0, 0, 0,
+]);
+/// Address of the EpochRewards sysvar, SysvarEpochRewards1111111111111111111111111.
+pub const EPOCH_REWARDS_ID: Pubkey = Pubkey::new_from_array([
+    6, 167, 213, 23, 24, 220, 63, 238, 2, 165, 88, 191, 131, 206, 102, 225, 68, 66, 42, 28, 52,
+    149, 11, 39, 193, 134, 155, 90, 156, 0, 0, 0,
+]);
+/// Address of the LastRestartSlot sysvar, SysvarLastRestartS1ot1111111111111111111111.
+pub const LAST_RESTART_SLOT_ID: Pubkey = Pubkey::new_from_array([
+    6, 167, 213, 23, 25, 6, 221, 225, 205, 63, 148, 125, 202, 180, 200, 244, 244, 245, 27, 173, 15,
+    152, 19, 184, 0, 210, 137, 71, 31, 192, 0, 0,
+]);
+
+/// Solana's default slots per epoch.
+pub const DEFAULT_SLOTS_PER_EPOCH: u64 = 432_000;
+/// Length of the shortest epoch while warming up.
+pub const MINIMUM_SLOTS_PER_EPOCH: u64 = 32;
+/// Solana's default rent in lamports per byte-year.
+pub const DEFAULT_LAMPORTS_PER_BYTE_YEAR: u64 = 3_480;
+/// Years of rent an account must hold to be rent exempt.
+pub const DEFAULT_EXEMPTION_THRESHOLD: f64 = 2.0;
+/// Percentage of collected rent that is burned.
+pub const DEFAULT_BURN_PERCENT: u8 = 50;
+
+/// Every sysvar available to the program.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SysvarCache {
+    pub clock: Clock,
+    pub rent: Rent,
+    pub epoch_schedule: EpochSchedule,
+    pub epoch_rewards: EpochRewards,
+    pub last_restart_slot: LastRestartSlot,
+}
+
+impl SysvarCache {
+    /// The account data of the sysvar at `id`, or `None` if it is not served.
+    pub fn account_data(&self, id: &Pubkey) -> Option<Vec<u8>> {
+        match *id {
+            CLOCK_ID => Some(self.clock.to_account_data()),
+            RENT_ID => Some(self.rent.to_account_data()),
+            EPOCH_SCHEDULE_ID => Some(self.epoch_schedule.to_account_data()),
+            EPOCH_REWARDS_ID => Some(self.epoch_rewards.to_account_data()),
+            LAST_RESTART_SLOT_ID => Some(self.last_restart_slot.to_account_data()),
+            _ => None,
+        }
+    }
+}
+
+/// Cluster time as of the declared slot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Clock {
+    pub slot: u64,
+    pub epoch_start_timestamp: i64,
+    pub epoch: u64,
+    pub leader_schedule_epoch: u64,
+    pub unix_timestamp: i64,
+}
+
+impl Clock {
+    /// Bincode encoding, as stored in the sysvar account.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        [
+            self.slot.to_le_bytes(),
+            self.epoch_start_timestamp.to_le_bytes(),
+            self.epoch.to_le_bytes(),
+            self.leader_schedule_epoch.to_le_bytes(),
+            self.unix_timestamp.to_le_bytes(),
+        ]
+        .concat()
+    }
+}
+
+/// Rent configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rent {
+    pub lamports_per_byte_year: u64,
+    pub exemption_threshold: f64,
+    pub burn_percent: u8,
+}
+
+impl Default for Rent {
+    fn default() -> Self {
+        Self {
+            lamports_per_byte_year: DEFAULT_LAMPORTS_PER_BYTE_YEAR,
+            exemption_threshold: DEFAULT_EXEMPTION_THRESHOLD,
+            burn_percent: DEFAULT_BURN_PERCENT,
+        }
+    }
+}
+
+impl Rent {
+    /// Bincode encoding, as stored in the sysvar account.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(17);
+        bytes.extend_from_slice(&self.lamports_per_byte_year.to_le_bytes());
+        bytes.extend_from_slice(&self.exemption_threshold.to_le_bytes());
+        bytes.push(self.burn_percent);
+        bytes
+    }
+}
+
+/// Epoch lengths, including the warmup period of shorter epochs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpochSchedule {
+    pub slots_per_epoch: u64,
+    pub leader_schedule_slot_offset: u64,
+    pub warmup: bool,
+    pub first_normal_epoch: u64,
+    pub first_normal_slot: u64,
+}
+
+impl Default for EpochSchedule {
+    /// Solana's default schedule: warmup epochs double from 32 slots up to 432,000.
+    fn default() -> Self {
+        let first_normal_epoch = DEFAULT_SLOTS_PER_EPOCH.next_power_of_two().trailing_zeros()
+            - MINIMUM_SLOTS_PER_EPOCH.trailing_zeros();
+        Self {
+            slots_per_epoch: DEFAULT_SLOTS_PER_EPOCH,
+            leader_schedule_slot_offset: DEFAULT_SLOTS_PER_EPOCH,
+            warmup: true,
+            first_normal_epoch: first_normal_epoch as u64,
+            first_normal_slot: ((1 << first_normal_epoch) - 1) * MINIMUM_SLOTS_PER_EPOCH,
+        }
+    }
+}
+
+impl EpochSchedule {
+    /// Bincode encoding, as stored in the sysvar account.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(33);
+        bytes.extend_from_slice(&self.slots_per_epoch.to_le_bytes());
+        bytes.extend_from_slice(&self.leader_schedule_slot_offset.to_le_bytes());
+        bytes.push(self.warmup as u8);
+        bytes.extend_from_slice(&self.first_normal_epoch.to_le_bytes());
+        bytes.extend_from_slice(&self.first_normal_slot.to_le_bytes());
+        bytes
+    }
+}
+
+/// Progress of the partitioned epoch rewards distribution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EpochRewards {
+    pub distribution_starting_block_height: u64,
+    pub num_partitions: u64,
+    pub parent_blockhash: Hash,
+    pub total_points: u128,
+    pub total_rewards: u64,
+    pub distributed_rewards: u64,
+    pub active: bool,
+}
+
+impl EpochRewards {
+    /// Bincode encoding, as stored in the sysvar account.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(81);
+        bytes.extend_from_slice(&self.distribution_starting_block_height.to_le_bytes());
+        bytes.extend_from_slice(&self.num_partitions.to_le_bytes());
+        bytes.extend_from_slice(&self.parent_blockhash);
+        bytes.extend_from_slice(&self.total_points.to_le_bytes());
+        bytes.extend_from_slice(&self.total_rewards.to_le_bytes());
+        bytes.extend_from_slice(&self.distributed_rewards.to_le_bytes());
+        bytes.push(self.active as u8);
+        bytes
+    }
+}
+
+/// Slot of the last cluster restart.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LastRestartSlot {
+    pub last_restart_slot: u64,
+}
+
+impl LastRestartSlot {
+    /// Bincode encoding, as stored in the sysvar account.
+    pub fn to_account_data(&self) -> Vec<u8> {
+        self.last_restart_slot.to_le_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_epoch_schedule() {
+        assert_eq!(
+            EpochSchedule::default(),
+            EpochSchedule {
+                slots_per_epoch: 432_000,
+                leader_schedule_slot_offset: 432_000,
+                warmup: true,
+                first_normal_epoch: 14,
+                first_normal_slot: 524_256,
+            }
+        );
+    }
+
+    #[test]
+    fn sysvar_ids() {
+        for (id, address) in [
+            (CLOCK_ID, "SysvarC1ock11111111111111111111111111111111"),
+            (RENT_ID, "SysvarRent111111111111111111111111111111111"),
+            (
+                EPOCH_SCHEDULE_ID,
+                "SysvarEpochSchedu1e111111111111111111111111",
+            ),
+            (
+                EPOCH_REWARDS_ID,
+                "SysvarEpochRewards1111111111111111111111111",
+            ),
+            (
+                LAST_RESTART_SLOT_ID,
+                "SysvarLastRestartS1ot1111111111111111111111",
+            ),
+        ] {
+            assert_eq!(id.to_string(), address);
+        }
+    }
+
+    #[test]
+    fn account_data_sizes() {
+        let sysvars = SysvarCache::default();
+        for (id, size) in [
+            (CLOCK_ID, 40),
+            (RENT_ID, 17),
+            (EPOCH_SCHEDULE_ID, 33),
+            (EPOCH_REWARDS_ID, 81),
+            (LAST_RESTART_SLOT_ID, 8),
+        ] {
+            assert_eq!(sysvars.account_data(&id).map(|data| data.len()), Some(size));
+        }
+        assert_eq!(sysvars.account_data(&Pubkey::default()), None);
+    }
+
+    #[test]
+    fn rent_account_data() {
+        // Default rent as read from mainnet's sysvar account
+        assert_eq!(
+            hex::encode(Rent::default().to_account_data()),
+            "980d000000000000000000000000004032"
+        );
+    }
+}
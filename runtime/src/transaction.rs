@@ -0,0 +1,235 @@
+// Copyright (c) 2025 Andy Bell <andyjsbell@gmail.com>
+// SPDX-License-Identifier: MIT
+
+//! Transaction messages in Solana's legacy format. A message lists every account the
+//! transaction touches once, and its instructions refer to them by index. The header
+//! says which accounts signed and which are read-only.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{journal::Hash, Pubkey};
+
+/// Signer and read-only account counts. Account keys are ordered writable signers,
+/// read-only signers, writable non-signers, then read-only non-signers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8,
+}
+
+/// An instruction whose program and accounts are indexes into the message's account keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+/// A transaction message: instructions run in order against a shared set of accounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub header: MessageHeader,
+    pub account_keys: Vec<Pubkey>,
+    pub recent_blockhash: Hash,
+    pub instructions: Vec<CompiledInstruction>,
+}
+
+impl Message {
+    /// Whether the account at `index` signed the transaction.
+    pub fn is_signer(&self, index: usize) -> bool {
+        index < self.header.num_required_signatures as usize
+    }
+
+    /// Whether the account at `index` may be written. Accounts invoked as programs
+    /// are read-only whatever their position, as on Solana.
+    pub fn is_writable(&self, index: usize) -> bool {
+        let num_signed = self.header.num_required_signatures as usize;
+        let maybe_writable = if index < num_signed {
+            index < num_signed.saturating_sub(self.header.num_readonly_signed_accounts as usize)
+        } else {
+            index
+                < self
+                    .account_keys
+                    .len()
+                    .saturating_sub(self.header.num_readonly_unsigned_accounts as usize)
+        };
+        maybe_writable && !self.is_invoked(index)
+    }
+
+    /// Whether any instruction invokes the account at `index` as its program.
+    pub fn is_invoked(&self, index: usize) -> bool {
+        self.instructions
+            .iter()
+            .any(|instruction| instruction.program_id_index as usize == index)
+    }
+
+    /// Checks the message is well formed, with the same rules Solana applies before
+    /// loading a transaction.
+    pub fn sanitize(&self) -> Result<(), String> {
+        let num_keys = self.account_keys.len();
+        let header = &self.header;
+        if header.num_required_signatures as usize + header.num_readonly_unsigned_accounts as usize
+            > num_keys
+        {
+            return Err("Header counts more accounts than the message has".to_string());
+        }
+        // The fee payer is the first signer and must be writable
+        if header.num_readonly_signed_accounts >= header.num_required_signatures {
+            return Err("Message has no writable signer to pay fees".to_string());
+        }
+        if num_keys > u8::MAX as usize + 1 {
+            return Err(format!("Too many account keys ({})", num_keys));
+        }
+        for (index, key) in self.account_keys.iter().enumerate() {
+            if self.account_keys[..index].contains(key) {
+                return Err(format!("Account {} is loaded twice", key));
+            }
+        }
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            let program_id_index = instruction.program_id_index as usize;
+            // The fee payer cannot be invoked
+            if program_id_index == 0 || program_id_index >= num_keys {
+                return Err(format!(
+                    "Instruction {} has invalid program index {}",
+                    index, program_id_index
+                ));
+            }
+            if let Some(account) = instruction
+                .accounts
+                .iter()
+                .find(|account| **account as usize >= num_keys)
+            {
+                return Err(format!(
+                    "Instruction {} has invalid account index {}",
+                    index, account
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The message in Solana's legacy wire format, the bytes a transaction's signatures cover.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = vec![
+            self.header.num_required_signatures,
+            self.header.num_readonly_signed_accounts,
+            self.header.num_readonly_unsigned_accounts,
+        ];
+        write_compact_u16(&mut bytes, self.account_keys.len());
+        for key in &self.account_keys {
+            bytes.extend_from_slice(key.as_ref());
+        }
+        bytes.extend_from_slice(&self.recent_blockhash);
+        write_compact_u16(&mut bytes, self.instructions.len());
+        for instruction in &self.instructions {
+            bytes.push(instruction.program_id_index);
+            write_compact_u16(&mut bytes, instruction.accounts.len());
+            bytes.extend_from_slice(&instruction.accounts);
+            write_compact_u16(&mut bytes, instruction.data.len());
+            bytes.extend_from_slice(&instruction.data);
+        }
+        bytes
+    }
+}
+
+/// Appends a length in Solana's compact-u16 encoding: seven bits per byte,
+/// least significant first, with the high bit set on every byte but the last.
+fn write_compact_u16(bytes: &mut Vec<u8>, len: usize) {
+    let mut value = len as u16;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> Message {
+        // Payer, read-only signer, writable account, program
+        Message {
+            header: MessageHeader {
+                num_required_signatures: 2,
+                num_readonly_signed_accounts: 1,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: (1..=4).map(|seed| Pubkey::from([seed; 32])).collect(),
+            recent_blockhash: [0; 32],
+            instructions: vec![CompiledInstruction {
+                program_id_index: 3,
+                accounts: vec![0, 1, 2],
+                data: vec![7],
+            }],
+        }
+    }
+
+    #[test]
+    fn account_privileges() {
+        let message = message();
+        assert_eq!(
+            (0..4).map(|i| message.is_signer(i)).collect::<Vec<_>>(),
+            [true, true, false, false]
+        );
+        assert_eq!(
+            (0..4).map(|i| message.is_writable(i)).collect::<Vec<_>>(),
+            [true, false, true, false]
+        );
+
+        // The invoked program stays read-only even when the header makes it writable
+        let mut message = message;
+        message.header.num_readonly_unsigned_accounts = 0;
+        assert!(!message.is_writable(3));
+    }
+
+    #[test]
+    fn sanitize() {
+        assert_eq!(message().sanitize(), Ok(()));
+
+        let mut no_payer = message();
+        no_payer.header.num_readonly_signed_accounts = 2;
+        assert!(no_payer.sanitize().is_err());
+
+        let mut duplicate_key = message();
+        duplicate_key.account_keys[2] = duplicate_key.account_keys[1];
+        assert!(duplicate_key.sanitize().is_err());
+
+        let mut invokes_payer = message();
+        invokes_payer.instructions[0].program_id_index = 0;
+        assert!(invokes_payer.sanitize().is_err());
+
+        let mut unknown_account = message();
+        unknown_account.instructions[0].accounts.push(4);
+        assert!(unknown_account.sanitize().is_err());
+    }
+
+    #[test]
+    fn compact_u16() {
+        for (len, expected) in [
+            (0, vec![0x00]),
+            (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x01]),
+            (0x3fff, vec![0xff, 0x7f]),
+            (0x4000, vec![0x80, 0x80, 0x01]),
+        ] {
+            let mut bytes = vec![];
+            write_compact_u16(&mut bytes, len);
+            assert_eq!(bytes, expected);
+        }
+    }
+
+    #[test]
+    fn serialize_layout() {
+        let bytes = message().serialize();
+        assert_eq!(bytes[..4], [2, 1, 1, 4]);
+        // Header, keys, blockhash, then one instruction: program index, 3 accounts, 1 byte of data
+        assert_eq!(bytes.len(), 3 + 1 + 4 * 32 + 32 + 1 + 1 + 1 + 3 + 1 + 1);
+        assert_eq!(bytes[bytes.len() - 8..], [1, 3, 3, 0, 1, 2, 1, 7]);
+    }
+}